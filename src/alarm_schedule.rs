@@ -5,7 +5,8 @@ use tokio_util::sync::CancellationToken;
 use crate::{
     C,
     app_error::AppError,
-    db::{ModelAlarm, ModelTimezone},
+    db::{ModelActiveAlarm, ModelAlarm, ModelAnimation, ModelCurve, ModelSunset, ModelTimezone},
+    light::{AlarmSequence, BrightnessCurve, SunsetSequence},
     message_handler::Msg,
     sleep,
 };
@@ -37,21 +38,106 @@ impl AlarmSchedule {
     }
     /// Start the alarm looper thread
     pub async fn start_alarm_thread(&mut self, sqlite: &SqlitePool) -> Result<(), AppError> {
-        let futs = tokio::join!(ModelAlarm::get_all(sqlite), ModelTimezone::get(sqlite));
-        let (alarms, tz) = (futs.0?, futs.1.unwrap_or_default());
+        let futs = tokio::join!(
+            ModelAlarm::get_all(sqlite),
+            ModelAnimation::get_all(sqlite),
+            ModelSunset::get_all(sqlite),
+            ModelTimezone::get(sqlite),
+            ModelCurve::get(sqlite)
+        );
+        let (alarms, animations, sunsets, tz, curve) = (
+            futs.0?,
+            futs.1?,
+            futs.2?,
+            futs.3.unwrap_or_default(),
+            futs.4.unwrap_or_default().curve_name,
+        );
 
         let tx = C!(self.tx);
         let token = self.get_set_cancel_token();
         tokio::spawn(async move {
             token
-                .run_until_cancelled(Self::init_alarm_loop(alarms, tz, tx))
+                .run_until_cancelled(Self::init_alarm_loop(
+                    alarms, animations, sunsets, tz, curve, tx,
+                ))
                 .await
         });
         Ok(())
     }
 
+    /// Create the alarm sequence for a stored alarm
+    fn sequence(
+        alarm: &ModelAlarm,
+        animations: &[ModelAnimation],
+        curve: BrightnessCurve,
+    ) -> AlarmSequence {
+        let profile = animations
+            .iter()
+            .find(|i| Some(i.animation_id) == alarm.animation_id)
+            .map(|i| C!(i.animation));
+        AlarmSequence::new(curve, alarm.ramp_minutes, alarm.hold_minutes)
+            .with_alarm_id(alarm.alarm_id)
+            .with_profile(profile)
+            .with_escalation(alarm.escalation, alarm.escalation_minutes)
+    }
+
+    /// Recreate the alarm sequence that was in progress when the app was last stopped, still snoozed if the snooze hasn't yet ended
+    /// `None`, and the active alarm is removed, if the sequence would have since finished
+    pub async fn resume(sqlite: &SqlitePool) -> Option<AlarmSequence> {
+        let active = ModelActiveAlarm::get(sqlite).await?;
+        let (alarms, animations, curve) = tokio::join!(
+            ModelAlarm::get_all(sqlite),
+            ModelAnimation::get_all(sqlite),
+            ModelCurve::get(sqlite)
+        );
+        let sequence = alarms
+            .unwrap_or_default()
+            .iter()
+            .find(|i| i.alarm_id == active.alarm_id)
+            .map(|alarm| {
+                Self::sequence(
+                    alarm,
+                    &animations.unwrap_or_default(),
+                    curve.unwrap_or_default().curve_name,
+                )
+                .with_elapsed(active.elapsed())
+            })
+            .map(|mut sequence| {
+                if let Some(remaining) = active.snooze_remaining() {
+                    sequence.snooze(remaining);
+                }
+                sequence
+            })
+            .filter(|sequence| !sequence.finished(sequence.elapsed()));
+        if sequence.is_none() {
+            ModelActiveAlarm::delete(sqlite).await.ok();
+        }
+        sequence
+    }
+
+    /// The alarm, and the sunset, due to start at a given (day, hour, minute), each is found independently of the other
+    fn due<'a>(
+        alarms: &'a [ModelAlarm],
+        sunsets: &'a [ModelSunset],
+        time: (i8, i8, i8),
+    ) -> (Option<&'a ModelAlarm>, Option<&'a ModelSunset>) {
+        (
+            alarms.iter().find(|i| i.start_time() == time),
+            sunsets.iter().find(|i| (i.day, i.hour, i.minute) == time),
+        )
+    }
+
     /// loop every 1 second,check if current time & day matches alarm, and if so execute alarm illuminate
-    async fn init_alarm_loop(alarms: Vec<ModelAlarm>, time_zone: ModelTimezone, tx: Sender<Msg>) {
+    /// Sunsets are checked in the same way, and start a fade to off, a sunset due at the same time as an alarm is skipped
+    /// An alarm with an animation uses it as the sunrise profile
+    async fn init_alarm_loop(
+        alarms: Vec<ModelAlarm>,
+        animations: Vec<ModelAnimation>,
+        sunsets: Vec<ModelSunset>,
+        time_zone: ModelTimezone,
+        curve: BrightnessCurve,
+        tx: Sender<Msg>,
+    ) {
         loop {
             let start = std::time::Instant::now();
             let current_time = time_zone.to_time();
@@ -60,12 +146,29 @@ impl AlarmSchedule {
                 .weekday()
                 .to_monday_zero_offset();
 
-            if alarms.iter().filter(|i| i.day == week_day).any(|i| {
-                i.hour == current_time.hour()
-                    && i.minute == current_time.minute()
-                    && current_time.second() == 0
-            }) {
-                tx.send(Msg::StartAlarm).await.ok();
+            if current_time.second() == 0 {
+                let (alarm, sunset) = Self::due(
+                    &alarms,
+                    &sunsets,
+                    (week_day, current_time.hour(), current_time.minute()),
+                );
+                if let Some(alarm) = alarm {
+                    let sequence = Self::sequence(alarm, &animations, curve);
+                    tx.send(Msg::StartAlarm(sequence)).await.ok();
+                }
+                if let Some(sunset) = sunset {
+                    if let Some(alarm) = alarm {
+                        tracing::info!(
+                            "Sunset {} skipped, alarm {} starts at the same time",
+                            sunset.sunset_id,
+                            alarm.alarm_id
+                        );
+                    } else {
+                        let sequence =
+                            SunsetSequence::new(sunset.brightness, sunset.colour, sunset.minutes);
+                        tx.send(Msg::StartSunset(sequence)).await.ok();
+                    }
+                }
             }
             sleep!(ONE_SECOND_AS_MS.saturating_sub(
                 u64::try_from(start.elapsed().as_millis()).unwrap_or(ONE_SECOND_AS_MS)
@@ -73,3 +176,121 @@ impl AlarmSchedule {
         }
     }
 }
+
+/// AlarmSchedule tests
+///
+/// cargo watch -q -c -w src/ -x 'test alarm_schedule -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        db::AlarmOptions,
+        tests::{test_cleanup, test_setup},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn alarm_schedule_resume_in_progress() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let options = AlarmOptions {
+            ramp_minutes: Some(60),
+            hold_minutes: Some(0),
+            ..AlarmOptions::default()
+        };
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &options).await.unwrap();
+        assert!(AlarmSchedule::resume(&db).await.is_none());
+
+        ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::from_secs(30 * 60), None)
+            .await
+            .unwrap();
+
+        let sequence = AlarmSchedule::resume(&db).await.unwrap();
+        assert_eq!(sequence.alarm_id(), Some(alarm.alarm_id));
+        let elapsed = sequence.elapsed();
+        assert!(elapsed >= Duration::from_secs(30 * 60) && elapsed < Duration::from_secs(31 * 60));
+        assert_eq!(sequence.step(elapsed), 6);
+        assert!(ModelActiveAlarm::get(&db).await.is_some());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn alarm_schedule_resume_finished() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let options = AlarmOptions {
+            ramp_minutes: Some(60),
+            hold_minutes: Some(0),
+            ..AlarmOptions::default()
+        };
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &options).await.unwrap();
+        ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::from_secs(61 * 60), None)
+            .await
+            .unwrap();
+
+        assert!(AlarmSchedule::resume(&db).await.is_none());
+        assert!(ModelActiveAlarm::get(&db).await.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn alarm_schedule_due_same_minute() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &AlarmOptions::default())
+            .await
+            .unwrap();
+        let sunset = ModelSunset::add(&db, (1, 6, 30), None, None, None)
+            .await
+            .unwrap();
+        let other = ModelSunset::add(&db, (2, 21, 0), None, None, None)
+            .await
+            .unwrap();
+        let alarms = [alarm.clone()];
+        let sunsets = [sunset.clone(), other.clone()];
+
+        // Both are found, so that the skipped sunset can be logged
+        let (due_alarm, due_sunset) = AlarmSchedule::due(&alarms, &sunsets, (1, 6, 30));
+        assert_eq!(due_alarm, Some(&alarm));
+        assert_eq!(due_sunset, Some(&sunset));
+
+        let (due_alarm, due_sunset) = AlarmSchedule::due(&alarms, &sunsets, (2, 21, 0));
+        assert!(due_alarm.is_none());
+        assert_eq!(due_sunset, Some(&other));
+
+        assert_eq!(
+            AlarmSchedule::due(&alarms, &sunsets, (1, 6, 31)),
+            (None, None)
+        );
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn alarm_schedule_resume_snoozed() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let options = AlarmOptions {
+            ramp_minutes: Some(60),
+            hold_minutes: Some(0),
+            ..AlarmOptions::default()
+        };
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &options).await.unwrap();
+        ModelActiveAlarm::set(
+            &db,
+            alarm.alarm_id,
+            Duration::from_secs(30 * 60),
+            Some(Duration::from_secs(9 * 60)),
+        )
+        .await
+        .unwrap();
+
+        // Restarted part way through the snooze, the sequence is still paused at the point it was snoozed
+        let sequence = AlarmSchedule::resume(&db).await.unwrap();
+        let elapsed = sequence.elapsed();
+        assert!(elapsed >= Duration::from_secs(30 * 60) && elapsed < Duration::from_secs(31 * 60));
+        let remaining = sequence.snooze_remaining().unwrap();
+        assert!(
+            remaining > Duration::from_secs(8 * 60) && remaining <= Duration::from_secs(9 * 60)
+        );
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
@@ -1,11 +1,54 @@
-use std::{collections::HashMap, env, time::SystemTime};
+use std::{collections::HashMap, env, ops::RangeInclusive, str::FromStr, time::SystemTime};
 
 use jiff::tz::TimeZone;
 
-use crate::app_error::AppError;
+use crate::{app_error::AppError, blinkt};
 
 type EnvHashMap = HashMap<String, String>;
 
+/// Where the pixel data is sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LedOutput {
+    /// The LED strip, using the configured transport
+    #[default]
+    Hardware,
+    /// No hardware, frames are captured in memory, so the app can run away from a Pi
+    Recording,
+    /// No hardware, frames are printed to the terminal
+    Terminal,
+}
+
+/// How the pixel data is sent to the LED strip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedTransport {
+    /// Bit-banged over two GPIO pins, using BCM pin numbers
+    Bitbang { pin_data: u8, pin_clock: u8 },
+    /// Hardware SPI, on the given bus
+    Spi { bus: u8, clock_speed_hz: u32 },
+}
+
+/// The LED strip, and how to reach it, defaults to a Pimoroni Blinkt!
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedStrip {
+    pub num_pixels: usize,
+    /// Frames are scaled down to keep the estimated current within this many milliamps, `None` when unlimited
+    pub power_budget_ma: Option<u32>,
+    pub transport: LedTransport,
+}
+
+impl Default for LedStrip {
+    fn default() -> Self {
+        Self {
+            num_pixels: blinkt::NUM_PIXELS,
+            power_budget_ma: None,
+            transport: LedTransport::Bitbang {
+                pin_data: blinkt::DAT,
+                pin_clock: blinkt::CLK,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppEnv {
     pub status_file_name: String,
@@ -19,6 +62,8 @@ pub struct AppEnv {
     pub ws_apikey: String,
     pub ws_password: String,
     pub ws_token_address: String,
+    pub led_output: LedOutput,
+    pub led_strip: LedStrip,
 }
 
 impl AppEnv {
@@ -76,6 +121,68 @@ impl AppEnv {
         }
     }
 
+    /// Parse a number, using the default if missing, an error if it can't be parsed or is outside of the range
+    fn parse_number<T: FromStr + PartialOrd>(
+        key: &str,
+        map: &EnvHashMap,
+        default: T,
+        range: RangeInclusive<T>,
+    ) -> Result<T, AppError> {
+        let Some(value) = map.get(key) else {
+            return Ok(default);
+        };
+        value
+            .parse::<T>()
+            .ok()
+            .filter(|value| range.contains(value))
+            .ok_or_else(|| AppError::InvalidEnv(key.into()))
+    }
+
+    /// Parse the LED strip length and transport, "spi" uses hardware SPI, anything else bit-bangs over GPIO pins
+    fn parse_led_strip(map: &EnvHashMap) -> Result<LedStrip, AppError> {
+        let transport = if map
+            .get("LED_TRANSPORT")
+            .is_some_and(|value| value.eq_ignore_ascii_case("spi"))
+        {
+            LedTransport::Spi {
+                bus: Self::parse_number("LED_SPI_BUS", map, 0, 0..=6)?,
+                clock_speed_hz: Self::parse_number(
+                    "LED_SPI_CLOCK_HZ",
+                    map,
+                    1_000_000,
+                    1..=32_000_000,
+                )?,
+            }
+        } else {
+            LedTransport::Bitbang {
+                pin_data: Self::parse_number("LED_PIN_DATA", map, blinkt::DAT, 0..=27)?,
+                pin_clock: Self::parse_number("LED_PIN_CLOCK", map, blinkt::CLK, 0..=27)?,
+            }
+        };
+        Ok(LedStrip {
+            num_pixels: Self::parse_number(
+                "LED_PIXELS",
+                map,
+                blinkt::NUM_PIXELS,
+                1..=blinkt::MAX_PIXELS,
+            )?,
+            power_budget_ma: map
+                .contains_key("LED_POWER_BUDGET_MA")
+                .then(|| Self::parse_number("LED_POWER_BUDGET_MA", map, 0, 1..=1_000_000))
+                .transpose()?,
+            transport,
+        })
+    }
+
+    /// Parse "recording" or "terminal" into an `LedOutput`, else `LedOutput::Hardware`
+    fn parse_led_output(map: &EnvHashMap) -> LedOutput {
+        match map.get("LED_OUTPUT").map(|i| i.to_lowercase()).as_deref() {
+            Some("recording") => LedOutput::Recording,
+            Some("terminal") => LedOutput::Terminal,
+            _ => LedOutput::Hardware,
+        }
+    }
+
     /// Load, and parse .env file, return `AppEnv`
     fn generate() -> Result<Self, AppError> {
         let env_map = env::vars()
@@ -92,6 +199,8 @@ impl AppEnv {
                 &env_map,
             )?)?,
             location_sqlite: Self::parse_db_name("LOCATION_SQLITE", &env_map)?,
+            led_output: Self::parse_led_output(&env_map),
+            led_strip: Self::parse_led_strip(&env_map)?,
             log_level: Self::parse_log(&env_map),
             start_time: SystemTime::now(),
             status_file_name: Self::parse_string("STATUS_FILE_NAME", &env_map)?,
@@ -316,6 +425,120 @@ mod tests {
         assert_eq!(result.iana_name(), Some("UTC"));
     }
 
+    #[test]
+    fn env_parse_led_output() {
+        let map = HashMap::new();
+        assert_eq!(AppEnv::parse_led_output(&map), LedOutput::Hardware);
+
+        let map = HashMap::from([(S!("LED_OUTPUT"), S!("hardware"))]);
+        assert_eq!(AppEnv::parse_led_output(&map), LedOutput::Hardware);
+
+        let map = HashMap::from([(S!("LED_OUTPUT"), S!("random"))]);
+        assert_eq!(AppEnv::parse_led_output(&map), LedOutput::Hardware);
+
+        let map = HashMap::from([(S!("LED_OUTPUT"), S!("recording"))]);
+        assert_eq!(AppEnv::parse_led_output(&map), LedOutput::Recording);
+
+        let map = HashMap::from([(S!("LED_OUTPUT"), S!("Recording"))]);
+        assert_eq!(AppEnv::parse_led_output(&map), LedOutput::Recording);
+
+        let map = HashMap::from([(S!("LED_OUTPUT"), S!("terminal"))]);
+        assert_eq!(AppEnv::parse_led_output(&map), LedOutput::Terminal);
+    }
+
+    #[test]
+    fn env_parse_led_strip_default() {
+        let map = HashMap::new();
+        assert_eq!(AppEnv::parse_led_strip(&map).unwrap(), LedStrip::default());
+
+        let map = HashMap::from([(S!("LED_TRANSPORT"), S!("bitbang"))]);
+        assert_eq!(AppEnv::parse_led_strip(&map).unwrap(), LedStrip::default());
+    }
+
+    #[test]
+    fn env_parse_led_strip_ok() {
+        let map = HashMap::from([
+            (S!("LED_PIXELS"), S!("144")),
+            (S!("LED_PIN_DATA"), S!("10")),
+            (S!("LED_PIN_CLOCK"), S!("11")),
+        ]);
+        assert_eq!(
+            AppEnv::parse_led_strip(&map).unwrap(),
+            LedStrip {
+                num_pixels: 144,
+                power_budget_ma: None,
+                transport: LedTransport::Bitbang {
+                    pin_data: 10,
+                    pin_clock: 11
+                }
+            }
+        );
+
+        let map = HashMap::from([
+            (S!("LED_TRANSPORT"), S!("SPI")),
+            (S!("LED_PIXELS"), S!("60")),
+            (S!("LED_SPI_BUS"), S!("1")),
+            (S!("LED_SPI_CLOCK_HZ"), S!("8000000")),
+            (S!("LED_POWER_BUDGET_MA"), S!("2500")),
+        ]);
+        assert_eq!(
+            AppEnv::parse_led_strip(&map).unwrap(),
+            LedStrip {
+                num_pixels: 60,
+                power_budget_ma: Some(2500),
+                transport: LedTransport::Spi {
+                    bus: 1,
+                    clock_speed_hz: 8_000_000
+                }
+            }
+        );
+
+        // Defaults for anything missing
+        let map = HashMap::from([(S!("LED_TRANSPORT"), S!("spi"))]);
+        assert_eq!(
+            AppEnv::parse_led_strip(&map).unwrap(),
+            LedStrip {
+                num_pixels: blinkt::NUM_PIXELS,
+                power_budget_ma: None,
+                transport: LedTransport::Spi {
+                    bus: 0,
+                    clock_speed_hz: 1_000_000
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn env_parse_led_strip_err() {
+        for (key, value) in [
+            ("LED_PIXELS", "0"),
+            ("LED_PIXELS", "100000"),
+            ("LED_PIXELS", "eight"),
+            ("LED_PIN_DATA", "28"),
+            ("LED_PIN_CLOCK", "-1"),
+            ("LED_POWER_BUDGET_MA", "0"),
+            ("LED_POWER_BUDGET_MA", "2A"),
+        ] {
+            let map = HashMap::from([(S!(key), S!(value))]);
+            match AppEnv::parse_led_strip(&map).unwrap_err() {
+                AppError::InvalidEnv(result) => assert_eq!(result, key),
+                _ => unreachable!(),
+            }
+        }
+
+        for (key, value) in [
+            ("LED_SPI_BUS", "7"),
+            ("LED_SPI_CLOCK_HZ", "0"),
+            ("LED_SPI_CLOCK_HZ", "1MHz"),
+        ] {
+            let map = HashMap::from([(S!("LED_TRANSPORT"), S!("spi")), (S!(key), S!(value))]);
+            match AppEnv::parse_led_strip(&map).unwrap_err() {
+                AppError::InvalidEnv(result) => assert_eq!(result, key),
+                _ => unreachable!(),
+            }
+        }
+    }
+
     #[test]
     fn env_panic_appenv() {
         let result = AppEnv::generate();
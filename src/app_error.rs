@@ -10,6 +10,8 @@ pub enum AppError {
     DbNameInvalid(String),
     #[error("'{0}' - file not found'")]
     FileNotFound(String),
+    #[error("invalid env: '{0}'")]
+    InvalidEnv(String),
     #[error("missing env: '{0}'")]
     MissingEnv(String),
     #[error(transparent)]
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+/// Gamma, in hundredths, that leaves a channel unchanged
+pub const LINEAR_GAMMA: u16 = 100;
+
+/// Per channel correction, applied to the colour values just before they are sent to the pixels
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalibrationSettings {
+    /// Red, green, and blue gamma, in hundredths, so `220` is a gamma of 2.2
+    pub gamma: (u16, u16, u16),
+    /// The red, green, and blue values that full white is scaled to
+    pub white_point: (u8, u8, u8),
+}
+
+impl Default for CalibrationSettings {
+    fn default() -> Self {
+        Self {
+            gamma: (LINEAR_GAMMA, LINEAR_GAMMA, LINEAR_GAMMA),
+            white_point: (255, 255, 255),
+        }
+    }
+}
+
+/// Lookup tables built from a set of calibration settings
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    settings: CalibrationSettings,
+    red: [u8; 256],
+    green: [u8; 256],
+    blue: [u8; 256],
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::new(CalibrationSettings::default())
+    }
+}
+
+impl Calibration {
+    pub fn new(settings: CalibrationSettings) -> Self {
+        Self {
+            settings,
+            red: Self::table(settings.gamma.0, settings.white_point.0),
+            green: Self::table(settings.gamma.1, settings.white_point.1),
+            blue: Self::table(settings.gamma.2, settings.white_point.2),
+        }
+    }
+
+    /// Build the table for a single channel, mapping every input value to its corrected output
+    fn table(gamma: u16, white: u8) -> [u8; 256] {
+        let gamma = f32::from(gamma) / f32::from(LINEAR_GAMMA);
+        let white = f32::from(white);
+        let mut table = [0u8; 256];
+        for (input, output) in table.iter_mut().enumerate() {
+            let value = (input as f32 / 255.0).powf(gamma) * white;
+            *output = value.round().clamp(0.0, 255.0) as u8;
+        }
+        table
+    }
+
+    pub const fn settings(&self) -> CalibrationSettings {
+        self.settings
+    }
+
+    /// Correct the bytes of a single pixel, in the order brightness, blue, green, red
+    pub fn apply(&self, bytes: &[u8]) -> [u8; 4] {
+        let byte = |index: usize| bytes.get(index).copied().unwrap_or_default();
+        [
+            byte(0),
+            self.blue[usize::from(byte(1))],
+            self.green[usize::from(byte(2))],
+            self.red[usize::from(byte(3))],
+        ]
+    }
+}
+
+/// Calibration tests
+///
+/// cargo watch -q -c -w src/ -x 'test blinkt_calibration -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinkt_calibration_default_unchanged() {
+        let calibration = Calibration::default();
+        for value in 0..=255u8 {
+            let bytes = [0b1111_1111, value, value, value];
+            assert_eq!(calibration.apply(&bytes), bytes);
+        }
+    }
+
+    #[test]
+    fn blinkt_calibration_white_point() {
+        let calibration = Calibration::new(CalibrationSettings {
+            white_point: (255, 180, 200),
+            ..CalibrationSettings::default()
+        });
+        // Brightness, blue, green, red
+        assert_eq!(
+            calibration.apply(&[0b1110_0001, 255, 255, 255]),
+            [0b1110_0001, 200, 180, 255]
+        );
+        assert_eq!(
+            calibration.apply(&[0b1110_0001, 0, 0, 0]),
+            [0b1110_0001, 0, 0, 0]
+        );
+        assert_eq!(
+            calibration.apply(&[0b1110_0001, 15, 200, 255]),
+            [0b1110_0001, 12, 141, 255]
+        );
+    }
+
+    #[test]
+    fn blinkt_calibration_gamma() {
+        let calibration = Calibration::new(CalibrationSettings {
+            gamma: (220, 100, 50),
+            ..CalibrationSettings::default()
+        });
+        let [_, blue, green, red] = calibration.apply(&[0b1111_1111, 128, 128, 128]);
+        assert_eq!(green, 128);
+        assert!(red < 64);
+        assert!(blue > 175);
+
+        // End points are unchanged
+        assert_eq!(
+            calibration.apply(&[0b1111_1111, 255, 255, 255]),
+            [0b1111_1111, 255, 255, 255]
+        );
+        assert_eq!(
+            calibration.apply(&[0b1111_1111, 0, 0, 0]),
+            [0b1111_1111, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn blinkt_calibration_serialize() {
+        let settings = CalibrationSettings {
+            gamma: (220, 200, 210),
+            white_point: (255, 190, 170),
+        };
+        assert_eq!(
+            serde_json::to_string(&settings).unwrap_or_default(),
+            r#"{"gamma":[220,200,210],"white_point":[255,190,170]}"#
+        );
+    }
+}
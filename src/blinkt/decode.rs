@@ -0,0 +1,237 @@
+use std::fmt;
+
+use super::Pixel;
+
+const START_FRAME: [u8; 4] = [0; 4];
+const HEADER_MASK: u8 = 0b1110_0000;
+
+/// Ways in which a written frame can be malformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The first four bytes weren't all zero
+    StartFrame([u8; 4]),
+    /// The frame ended before every LED frame had been written
+    MissingPixels { expected: usize, found: usize },
+    /// An LED frame didn't start with `0b111`
+    Header { pixel: usize, byte: u8 },
+    /// Fewer end frame bytes than needed to clock the data through every pixel
+    EndFrameLength { expected: usize, found: usize },
+    /// The end frame wasn't all `0x00`, or all `0xFF`
+    EndFrame { index: usize, byte: u8 },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StartFrame(bytes) => write!(f, "invalid start frame: {bytes:02x?}"),
+            Self::MissingPixels { expected, found } => {
+                write!(f, "expected {expected} LED frames, found {found}")
+            }
+            Self::Header { pixel, byte } => {
+                write!(f, "invalid header on pixel {pixel}: {byte:#010b}")
+            }
+            Self::EndFrameLength { expected, found } => {
+                write!(
+                    f,
+                    "expected at least {expected} end frame bytes, found {found}"
+                )
+            }
+            Self::EndFrame { index, byte } => {
+                write!(f, "invalid end frame byte {index}: {byte:#04x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// A successfully decoded frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedFrame {
+    pub pixels: Vec<Pixel>,
+    /// The end frame is all zeros, and long enough to also act as the extra 32 bit reset frame needed by SK9822 LEDs
+    pub sk9822_compatible: bool,
+}
+
+/// Decode the bytes written by `Blinkt::show`, a start frame of four zero bytes, one four byte LED frame per pixel, then an end frame.
+/// The end frame needs at least one bit per two pixels, APA102 end frames may be `0xFF` bytes, but SK9822 LEDs only latch on zeros.
+pub fn decode(frame: &[u8], num_pixels: usize) -> Result<DecodedFrame, FrameError> {
+    let Some((start, rest)) = frame.split_first_chunk::<4>() else {
+        let mut bytes = [0xFF; 4];
+        bytes[..frame.len()].copy_from_slice(frame);
+        return Err(FrameError::StartFrame(bytes));
+    };
+    if *start != START_FRAME {
+        return Err(FrameError::StartFrame(*start));
+    }
+
+    let (led_frames, _) = rest.as_chunks::<4>();
+    if led_frames.len() < num_pixels {
+        return Err(FrameError::MissingPixels {
+            expected: num_pixels,
+            found: led_frames.len(),
+        });
+    }
+
+    let mut pixels = Vec::with_capacity(num_pixels);
+    for (pixel, led_frame) in led_frames.iter().take(num_pixels).enumerate() {
+        if led_frame[0] & HEADER_MASK != HEADER_MASK {
+            return Err(FrameError::Header {
+                pixel,
+                byte: led_frame[0],
+            });
+        }
+        pixels.push(Pixel::from_bytes(*led_frame));
+    }
+
+    let end_frame = &frame[4 + num_pixels * 4..];
+    let expected = num_pixels.div_ceil(16);
+    if end_frame.len() < expected {
+        return Err(FrameError::EndFrameLength {
+            expected,
+            found: end_frame.len(),
+        });
+    }
+    // Either fill byte is valid, but it must be used throughout
+    let fill = end_frame.first().copied().unwrap_or_default();
+    if let Some((index, byte)) = end_frame
+        .iter()
+        .enumerate()
+        .find(|(_, byte)| (**byte != 0x00 && **byte != 0xFF) || **byte != fill)
+    {
+        return Err(FrameError::EndFrame { index, byte: *byte });
+    }
+
+    Ok(DecodedFrame {
+        pixels,
+        sk9822_compatible: fill == 0x00 && end_frame.len() >= expected + 4,
+    })
+}
+
+/// Frame decoding tests
+///
+/// cargo watch -q -c -w src/ -x 'test blinkt_decode -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::super::{Blinkt, RecordingOutput};
+    use super::*;
+
+    fn show(num_pixels: usize, setup: impl FnOnce(&mut Blinkt)) -> Vec<u8> {
+        let output = RecordingOutput::new(num_pixels);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, num_pixels);
+        blinkt.set_clear_on_drop(false);
+        setup(&mut blinkt);
+        blinkt.show().unwrap();
+        recording.last().unwrap()
+    }
+
+    #[test]
+    fn blinkt_decode_show() {
+        for num_pixels in [1, 8, 16, 17, 144] {
+            let frame = show(num_pixels, |blinkt| {
+                blinkt.set_pixel_rgbb(0, 255, 128, 1, 0.5);
+            });
+            let decoded = decode(&frame, num_pixels).unwrap();
+
+            assert_eq!(decoded.pixels.len(), num_pixels);
+            assert_eq!(decoded.pixels[0].rgb(), (255, 128, 1));
+            assert_eq!(decoded.pixels[0].bytes()[0], 0b1110_1111);
+            assert!(
+                decoded.pixels[1..]
+                    .iter()
+                    .all(|pixel| *pixel == Pixel::default())
+            );
+            assert!(decoded.sk9822_compatible);
+        }
+    }
+
+    #[test]
+    fn blinkt_decode_start_frame() {
+        let mut frame = show(2, |_| {});
+        frame[1] = 1;
+        assert_eq!(decode(&frame, 2), Err(FrameError::StartFrame([0, 1, 0, 0])));
+        assert_eq!(
+            decode(&[0, 0], 2),
+            Err(FrameError::StartFrame([0, 0, 0xFF, 0xFF]))
+        );
+    }
+
+    #[test]
+    fn blinkt_decode_header() {
+        let mut frame = show(3, |_| {});
+        frame[4 + 4] = 0b1100_0111;
+        assert_eq!(
+            decode(&frame, 3),
+            Err(FrameError::Header {
+                pixel: 1,
+                byte: 0b1100_0111
+            })
+        );
+    }
+
+    #[test]
+    fn blinkt_decode_missing_pixels() {
+        let frame = show(2, |_| {});
+        assert_eq!(
+            decode(&frame[..10], 2),
+            Err(FrameError::MissingPixels {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn blinkt_decode_end_frame() {
+        let frame = show(32, |_| {});
+        let end = 4 + 32 * 4;
+
+        // Missing end frame
+        assert_eq!(
+            decode(&frame[..end], 32),
+            Err(FrameError::EndFrameLength {
+                expected: 2,
+                found: 0
+            })
+        );
+        assert_eq!(
+            decode(&frame[..=end], 32),
+            Err(FrameError::EndFrameLength {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        // Long enough for APA102, but not SK9822
+        let decoded = decode(&frame[..end + 2], 32).unwrap();
+        assert!(!decoded.sk9822_compatible);
+
+        // APA102 style 0xFF end frame
+        let mut apa102 = frame[..end].to_vec();
+        apa102.extend_from_slice(&[0xFF; 6]);
+        let decoded = decode(&apa102, 32).unwrap();
+        assert_eq!(decoded.pixels.len(), 32);
+        assert!(!decoded.sk9822_compatible);
+
+        // Mixed end frame
+        let mut mixed = frame.clone();
+        mixed[end + 1] = 0xFF;
+        assert_eq!(
+            decode(&mixed, 32),
+            Err(FrameError::EndFrame {
+                index: 1,
+                byte: 0xFF
+            })
+        );
+        mixed[end + 1] = 0x01;
+        assert_eq!(
+            decode(&mixed, 32),
+            Err(FrameError::EndFrame {
+                index: 1,
+                byte: 0x01
+            })
+        );
+    }
+}
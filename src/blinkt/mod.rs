@@ -19,14 +19,26 @@ use rppal::gpio::{Gpio, OutputPin};
 pub use rppal::gpio::Error as GpioError;
 pub use rppal::spi::Error as SpiError;
 
+mod calibration;
+pub mod decode;
 mod pixel;
+pub mod power;
+mod recording;
+mod terminal;
 
+pub use calibration::{Calibration, CalibrationSettings};
 pub use pixel::Pixel;
+#[cfg(test)]
+pub use recording::Recording;
+pub use recording::RecordingOutput;
+pub use terminal::TerminalOutput;
 
 // Default values for the Pimoroni Blinkt! board using BCM GPIO pin numbers
-const DAT: u8 = 23;
-const CLK: u8 = 24;
-const NUM_PIXELS: usize = 8;
+pub const DAT: u8 = 23;
+pub const CLK: u8 = 24;
+pub const NUM_PIXELS: usize = 8;
+/// Longest supported strip
+pub const MAX_PIXELS: usize = 1024;
 
 #[derive(Debug)]
 /// Errors that can occur while using Blinkt.
@@ -75,8 +87,15 @@ impl From<SpiError> for Error {
 /// Result type returned from methods that can have `blinkt::Error`s.
 pub type Result<T> = result::Result<T, Error>;
 
-trait SerialOutput {
+/// Destination for the bytes sent to the pixels.
+pub trait SerialOutput {
+    /// Writes part of a frame.
     fn write(&mut self, data: &[u8]) -> Result<()>;
+
+    /// Called once every part of a frame has been written.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 struct BlinktGpio {
@@ -137,6 +156,26 @@ impl BlinktSpi {
     ) -> Result<Self> {
         Ok(Self(spi::Spi::new(bus, slave, clock_speed_hz, mode)?))
     }
+
+    /// Constructs a new `BlinktSpi` on a numbered bus, using slave select 0 and mode 0.
+    pub fn with_bus(bus: u8, clock_speed_hz: u32) -> Result<Self> {
+        let bus = match bus {
+            0 => spi::Bus::Spi0,
+            1 => spi::Bus::Spi1,
+            2 => spi::Bus::Spi2,
+            3 => spi::Bus::Spi3,
+            4 => spi::Bus::Spi4,
+            5 => spi::Bus::Spi5,
+            6 => spi::Bus::Spi6,
+            _ => {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid SPI bus: {bus}"),
+                )));
+            }
+        };
+        Self::with_settings(bus, spi::SlaveSelect::Ss0, clock_speed_hz, spi::Mode::Mode0)
+    }
 }
 
 impl Default for BlinktSpi {
@@ -170,8 +209,12 @@ impl SerialOutput for BlinktSpi {
 pub struct Blinkt {
     serial_output: Box<dyn SerialOutput + Send>,
     pixels: Vec<Pixel>,
+    calibration: Calibration,
     clear_on_drop: bool,
     end_frame: Vec<u8>,
+    estimated_ma: f32,
+    power_budget_ma: Option<u32>,
+    last_frame: Option<Vec<Pixel>>,
 }
 
 impl Blinkt {
@@ -191,11 +234,32 @@ impl Blinkt {
         Ok(Self {
             serial_output: Box::new(BlinktGpio::with_settings(pin_data, pin_clock)?),
             pixels: vec![Pixel::default(); num_pixels],
+            calibration: Calibration::default(),
             clear_on_drop: true,
             end_frame: vec![0u8; 4 + (((num_pixels as f32 / 16.0f32) + 0.94f32) as usize)],
+            estimated_ma: 0.0,
+            power_budget_ma: None,
+            last_frame: None,
         })
     }
 
+    /// Constructs a new `Blinkt` using any serial output, with a custom number of pixels.
+    pub fn with_output(
+        serial_output: impl SerialOutput + Send + 'static,
+        num_pixels: usize,
+    ) -> Self {
+        Self {
+            serial_output: Box::new(serial_output),
+            pixels: vec![Pixel::default(); num_pixels],
+            calibration: Calibration::default(),
+            clear_on_drop: true,
+            end_frame: vec![0u8; 4 + (((num_pixels as f32 / 16.0f32) + 0.94f32) as usize)],
+            estimated_ma: 0.0,
+            power_budget_ma: None,
+            last_frame: None,
+        }
+    }
+
     /// Constructs a new `Blinkt` using hardware SPI, with custom settings for the
     /// clock speed and number of pixels.
     ///
@@ -212,8 +276,12 @@ impl Blinkt {
         Self {
             serial_output: Box::new(spi),
             pixels: vec![Pixel::default(); num_pixels],
+            calibration: Calibration::default(),
             clear_on_drop: true,
             end_frame: vec![0u8; 4 + (((num_pixels as f32 / 16.0f32) + 0.94f32) as usize)],
+            estimated_ma: 0.0,
+            power_budget_ma: None,
+            last_frame: None,
         }
     }
 
@@ -291,12 +359,47 @@ impl Blinkt {
 
     /// Sends the contents of the local buffer to the pixels, updating their
     /// LED colors and brightness.
-    pub fn show(&mut self) -> Result<()> {
+    ///
+    /// Nothing is sent if the frame is identical to the last one successfully
+    /// sent, use [`force_show`] to always send. Returns `true` if the frame was
+    /// sent, and `false` if it was skipped.
+    ///
+    /// [`force_show`]: #method.force_show
+    pub fn show(&mut self) -> Result<bool> {
+        // LED frames (3*1, 5*brightness, 8*blue, 8*green, 8*red), colour corrected by the calibration,
+        // then scaled down to stay within any power budget.
+        let mut frame = self
+            .pixels
+            .iter()
+            .map(|pixel| Pixel::from_bytes(self.calibration.apply(pixel.bytes())))
+            .collect::<Vec<_>>();
+        self.estimated_ma = match self.power_budget_ma {
+            Some(budget_ma) => power::limit(&mut frame, budget_ma),
+            None => power::estimate_ma(&frame),
+        };
+        if self.last_frame.as_ref() == Some(&frame) {
+            return Ok(false);
+        }
+
+        // Forget the last frame until this one has been sent, so a failed write is never skipped
+        self.last_frame = None;
+        self.write_frame(&frame)?;
+        self.last_frame = Some(frame);
+        Ok(true)
+    }
+
+    /// Sends the contents of the local buffer to the pixels, even if unchanged
+    /// since the last frame, to recover from a glitch on the line.
+    pub fn force_show(&mut self) -> Result<()> {
+        self.last_frame = None;
+        self.show().map(|_| ())
+    }
+
+    fn write_frame(&mut self, frame: &[Pixel]) -> Result<()> {
         // Start frame (32*0).
         self.serial_output.write(&[0u8; 4])?;
 
-        // LED frames (3*1, 5*brightness, 8*blue, 8*green, 8*red).
-        for pixel in &self.pixels {
+        for pixel in frame {
             self.serial_output.write(pixel.bytes())?;
         }
 
@@ -307,7 +410,32 @@ impl Blinkt {
         // compatible with both the APA102 and SK9822.
         self.serial_output.write(&self.end_frame)?;
 
-        Ok(())
+        self.serial_output.flush()
+    }
+
+    /// Returns the calibration applied to every pixel when shown.
+    pub const fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    /// Sets the calibration applied to every pixel when shown, the local buffer is left unchanged.
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = calibration;
+    }
+
+    /// Returns the estimated current drawn by the last frame shown, in milliamps.
+    pub const fn estimated_ma(&self) -> f32 {
+        self.estimated_ma
+    }
+
+    /// Returns the power budget, in milliamps, `None` when unlimited.
+    pub const fn power_budget(&self) -> Option<u32> {
+        self.power_budget_ma
+    }
+
+    /// Sets the power budget, in milliamps, every frame shown is scaled down to stay within it, the local buffer is left unchanged.
+    pub const fn set_power_budget(&mut self, power_budget_ma: Option<u32>) {
+        self.power_budget_ma = power_budget_ma;
     }
 
     /// Returns the value of `clear_on_drop`.
@@ -338,7 +466,7 @@ impl Drop for Blinkt {
     fn drop(&mut self) {
         if self.clear_on_drop {
             self.clear();
-            let _ = self.show();
+            let _ = self.force_show();
         }
     }
 }
@@ -1,4 +1,7 @@
 const DEFAULT_BRIGHTNESS: u8 = 7;
+const MAX_BRIGHTNESS: f32 = 31.0;
+// Stops float error pushing a level that sits exactly on a 5-bit step up to the next one
+const LEVEL_EPSILON: f32 = 0.0001;
 
 const IDX_BRIGHTNESS: usize = 0;
 const IDX_BLUE: usize = 1;
@@ -6,7 +9,7 @@ const IDX_GREEN: usize = 2;
 const IDX_RED: usize = 3;
 
 /// A pixel on an LED strip or board.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Pixel {
     value: [u8; 4], // Brightness, blue, green, red
 }
@@ -39,7 +42,7 @@ impl Pixel {
             self.value[IDX_RED],
             self.value[IDX_GREEN],
             self.value[IDX_BLUE],
-            f32::from(0b0001_1111 & self.value[IDX_BRIGHTNESS]) / 31.0,
+            f32::from(0b0001_1111 & self.value[IDX_BRIGHTNESS]) / MAX_BRIGHTNESS,
         )
     }
 
@@ -53,6 +56,27 @@ impl Pixel {
         self.set_brightness(brightness);
     }
 
+    /// Sets the values for red, green and blue, scaled to a given level.
+    ///
+    /// `red`, `green` and `blue` are specified as 8-bit values between `0` (0%) and `255` (100%).
+    /// `level` is specified as a floating point value between `0.0` (0%) and `1.0` (100%).
+    /// The lowest 5-bit brightness able to reach `level` is used, with the remainder made up by scaling
+    /// the colour channels, which gives far more distinct levels than the 5-bit brightness alone.
+    #[inline]
+    pub fn set_rgb_level(&mut self, red: u8, green: u8, blue: u8, level: f32) {
+        let level = level.clamp(0.0, 1.0);
+        let global = (level * MAX_BRIGHTNESS - LEVEL_EPSILON).ceil();
+        if global < 1.0 {
+            self.set_rgb(0, 0, 0);
+            self.value[IDX_BRIGHTNESS] = 0b1110_0000;
+            return;
+        }
+        let scale = level * MAX_BRIGHTNESS / global;
+        let channel = |value: u8| (f32::from(value) * scale).round().clamp(0.0, 255.0) as u8;
+        self.set_rgb(channel(red), channel(green), channel(blue));
+        self.value[IDX_BRIGHTNESS] = 0b1110_0000 | global as u8;
+    }
+
     /// Returns the red value.
     #[inline]
     pub const fn red(&self) -> u8 {
@@ -98,7 +122,7 @@ impl Pixel {
     /// Returns the brightness value.
     #[inline]
     pub fn brightness(&self) -> f32 {
-        f32::from(0b0001_1111 & self.value[IDX_BRIGHTNESS]) / 31.0
+        f32::from(0b0001_1111 & self.value[IDX_BRIGHTNESS]) / MAX_BRIGHTNESS
     }
 
     /// Sets the brightness value.
@@ -106,7 +130,8 @@ impl Pixel {
     /// `brightness` is specified as a floating point value between `0.0` (0%) and `1.0` (100%), and is converted to a 5-bit value.
     #[inline]
     pub fn set_brightness(&mut self, brightness: f32) {
-        self.value[IDX_BRIGHTNESS] = 0b1110_0000 | ((31.0 * brightness.clamp(0.0, 1.0)) as u8);
+        self.value[IDX_BRIGHTNESS] =
+            0b1110_0000 | ((MAX_BRIGHTNESS * brightness.clamp(0.0, 1.0)) as u8);
     }
 
     /// Sets the red, green and blue values to `0`.
@@ -119,6 +144,12 @@ impl Pixel {
     pub(crate) const fn bytes(&self) -> &[u8] {
         &self.value
     }
+
+    /// Creates a pixel from an LED frame, brightness, blue, green, red.
+    #[inline]
+    pub(crate) const fn from_bytes(value: [u8; 4]) -> Self {
+        Self { value }
+    }
 }
 
 impl Default for Pixel {
@@ -128,3 +159,48 @@ impl Default for Pixel {
         }
     }
 }
+
+/// Pixel tests
+///
+/// cargo watch -q -c -w src/ -x 'test blinkt_pixel -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinkt_pixel_set_rgb_level_full() {
+        let mut pixel = Pixel::default();
+        pixel.set_rgb_level(255, 200, 15, 1.0);
+        assert_eq!(pixel.bytes(), [0b1111_1111, 15, 200, 255]);
+    }
+
+    #[test]
+    fn blinkt_pixel_set_rgb_level_off() {
+        let mut pixel = Pixel::default();
+        pixel.set_rgb_level(255, 200, 15, 0.0);
+        assert_eq!(pixel.bytes(), [0b1110_0000, 0, 0, 0]);
+    }
+
+    #[test]
+    fn blinkt_pixel_set_rgb_level_scaled() {
+        let mut pixel = Pixel::default();
+        // Half of the lowest 5-bit brightness
+        pixel.set_rgb_level(255, 200, 16, 0.5 / 31.0);
+        assert_eq!(pixel.bytes(), [0b1110_0001, 8, 100, 128]);
+
+        // Exactly on a 5-bit step, channels unscaled
+        pixel.set_rgb_level(255, 200, 16, 10.0 / 31.0);
+        assert_eq!(pixel.bytes(), [0b1110_1010, 16, 200, 255]);
+    }
+
+    #[test]
+    fn blinkt_pixel_set_rgb_level_distinct() {
+        let mut pixel = Pixel::default();
+        let mut levels = std::collections::HashSet::new();
+        for i in 0..=1000u16 {
+            pixel.set_rgb_level(255, 255, 255, f32::from(i) / 1000.0);
+            levels.insert(pixel.bytes().to_vec());
+        }
+        assert!(levels.len() > 32 * 4);
+    }
+}
@@ -0,0 +1,104 @@
+use super::Pixel;
+
+/// Current drawn by a single colour channel at full value, and full global brightness, in milliamps
+const CHANNEL_MA: f32 = 20.0;
+
+/// Current drawn by every pixel, even when off, in milliamps
+const IDLE_MA: f32 = 1.0;
+
+/// Estimated current drawn by a single pixel, in milliamps, full white at full brightness is around 60mA.
+pub fn pixel_ma(pixel: &Pixel) -> f32 {
+    let (red, green, blue, brightness) = pixel.rgbb();
+    let channels = f32::from(red) + f32::from(green) + f32::from(blue);
+    IDLE_MA + channels / 255.0 * brightness * CHANNEL_MA
+}
+
+/// Estimated current drawn by a frame, in milliamps.
+pub fn estimate_ma(pixels: &[Pixel]) -> f32 {
+    pixels.iter().map(pixel_ma).sum()
+}
+
+/// Scales the colour channels of every pixel down by the same factor, so that the estimated current stays within the budget.
+///
+/// Returns the estimated current after scaling, which can only exceed the budget if the budget is below the idle current of the strip.
+pub fn limit(pixels: &mut [Pixel], budget_ma: u32) -> f32 {
+    let estimate = estimate_ma(pixels);
+    let budget = budget_ma as f32;
+    if estimate <= budget {
+        return estimate;
+    }
+    let idle = IDLE_MA * pixels.len() as f32;
+    let scale = ((budget - idle) / (estimate - idle)).clamp(0.0, 1.0);
+    // Rounded down, so the scaled frame never ends up over budget
+    let channel = |value: u8| (f32::from(value) * scale).floor() as u8;
+    for pixel in pixels.iter_mut() {
+        let (red, green, blue) = pixel.rgb();
+        pixel.set_rgb(channel(red), channel(green), channel(blue));
+    }
+    estimate_ma(pixels)
+}
+
+/// Power model tests
+///
+/// cargo watch -q -c -w src/ -x 'test blinkt_power -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(red: u8, green: u8, blue: u8, brightness: f32) -> Pixel {
+        let mut pixel = Pixel::default();
+        pixel.set_rgbb(red, green, blue, brightness);
+        pixel
+    }
+
+    #[test]
+    fn blinkt_power_estimate() {
+        assert!((pixel_ma(&pixel(255, 255, 255, 1.0)) - 61.0).abs() < 0.01);
+        assert!((pixel_ma(&pixel(255, 0, 0, 1.0)) - 21.0).abs() < 0.01);
+        assert!((pixel_ma(&pixel(0, 0, 0, 1.0)) - IDLE_MA).abs() < 0.01);
+        // Half of the 5-bit brightness, 15/31
+        assert!((pixel_ma(&pixel(255, 0, 0, 0.5)) - (1.0 + 20.0 * 15.0 / 31.0)).abs() < 0.01);
+
+        let frame = vec![pixel(255, 255, 255, 1.0); 144];
+        assert!((estimate_ma(&frame) - 144.0 * 61.0).abs() < 0.1);
+        assert!(estimate_ma(&[]).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn blinkt_power_limit_under_budget() {
+        let mut frame = vec![pixel(255, 128, 0, 1.0); 8];
+        let before = frame.clone();
+        let estimate = limit(&mut frame, 1000);
+        assert_eq!(frame, before);
+        assert!((estimate - estimate_ma(&before)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn blinkt_power_limit_over_budget() {
+        let mut frame = vec![pixel(255, 255, 255, 1.0); 144];
+        let estimate = limit(&mut frame, 2000);
+        assert!(estimate <= 2000.0);
+        assert!(estimate > 1900.0);
+        // Colours are scaled evenly, and the global brightness is untouched
+        let (red, green, blue, brightness) = frame[0].rgbb();
+        assert_eq!(red, green);
+        assert_eq!(green, blue);
+        assert!(red < 255);
+        assert!((brightness - 1.0).abs() < f32::EPSILON);
+
+        // Relative colours are kept
+        let mut frame = vec![pixel(200, 100, 0, 1.0); 60];
+        limit(&mut frame, 1000);
+        let (red, green, blue) = frame[0].rgb();
+        assert!(red.abs_diff(green * 2) <= 1);
+        assert_eq!(blue, 0);
+    }
+
+    #[test]
+    fn blinkt_power_limit_below_idle() {
+        let mut frame = vec![pixel(255, 255, 255, 1.0); 10];
+        let estimate = limit(&mut frame, 5);
+        assert!((estimate - 10.0 * IDLE_MA).abs() < f32::EPSILON);
+        assert!(frame.iter().all(|pixel| pixel.rgb() == (0, 0, 0)));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use super::{Pixel, Result, SerialOutput, decode::decode};
+
+/// Number of frames kept, older frames are dropped so that a long running recording doesn't grow forever
+const MAX_FRAMES: usize = 1024;
+
+/// Shared handle to the frames captured by a `RecordingOutput`, can be cloned and read whilst the output is in use.
+#[derive(Debug, Clone, Default)]
+pub struct Recording(Arc<Mutex<VecDeque<Vec<u8>>>>);
+
+#[cfg_attr(
+    not(test),
+    expect(
+        dead_code,
+        reason = "only read by tests, the app just needs the frames discarded"
+    )
+)]
+impl Recording {
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Vec<u8>>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Every captured frame, oldest first, each frame is the start frame, LED frames, and end frame, exactly as written.
+    pub fn frames(&self) -> Vec<Vec<u8>> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// The most recently captured frame.
+    pub fn last(&self) -> Option<Vec<u8>> {
+        self.lock().back().cloned()
+    }
+
+    /// Number of frames captured.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if no frames have been captured.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all captured frames.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn push(&self, frame: Vec<u8>) {
+        let mut frames = self.lock();
+        if frames.len() == MAX_FRAMES {
+            frames.pop_front();
+        }
+        frames.push_back(frame);
+    }
+}
+
+/// Output that doesn't need any hardware, every frame written by `Blinkt::show` is captured instead.
+/// Each frame is also decoded, and traced as the colour and brightness of every pixel, with any malformed frame logged as a warning.
+#[derive(Debug)]
+pub struct RecordingOutput {
+    current: Vec<u8>,
+    num_pixels: usize,
+    recording: Recording,
+}
+
+impl RecordingOutput {
+    pub fn new(num_pixels: usize) -> Self {
+        Self {
+            current: vec![],
+            num_pixels,
+            recording: Recording::default(),
+        }
+    }
+
+    /// Returns a handle to the captured frames.
+    #[cfg_attr(not(test), expect(dead_code, reason = "only read by tests"))]
+    pub fn recording(&self) -> Recording {
+        self.recording.clone()
+    }
+}
+
+impl SerialOutput for RecordingOutput {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.current.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let frame = std::mem::take(&mut self.current);
+        match decode(&frame, self.num_pixels) {
+            Ok(decoded) => tracing::trace!(
+                "frame: {:?}",
+                decoded.pixels.iter().map(Pixel::rgbb).collect::<Vec<_>>()
+            ),
+            Err(e) => tracing::warn!("Invalid frame: {e}, {frame:02x?}"),
+        }
+        self.recording.push(frame);
+        Ok(())
+    }
+}
+
+/// RecordingOutput tests
+///
+/// cargo watch -q -c -w src/ -x 'test blinkt_recording -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::super::Blinkt;
+    use super::*;
+
+    #[test]
+    fn blinkt_recording_show() {
+        let output = RecordingOutput::new(2);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 2);
+        blinkt.set_clear_on_drop(false);
+        assert!(recording.is_empty());
+
+        blinkt.set_pixel_rgbb(0, 255, 200, 15, 1.0);
+        blinkt.show().unwrap();
+
+        assert_eq!(recording.len(), 1);
+        assert_eq!(
+            recording.last().unwrap(),
+            vec![
+                0,
+                0,
+                0,
+                0, // Start frame
+                0b1111_1111,
+                15,
+                200,
+                255, // Pixel 0
+                0b1110_0111,
+                0,
+                0,
+                0, // Pixel 1
+                0,
+                0,
+                0,
+                0,
+                0 // End frame
+            ]
+        );
+
+        blinkt.clear();
+        blinkt.show().unwrap();
+        assert_eq!(recording.len(), 2);
+        assert_eq!(recording.frames()[1][4..8], [0b1111_1111, 0, 0, 0]);
+
+        recording.clear();
+        assert!(recording.is_empty());
+    }
+
+    #[test]
+    fn blinkt_recording_unchanged() {
+        let output = RecordingOutput::new(2);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 2);
+        blinkt.set_clear_on_drop(false);
+
+        blinkt.set_pixel(0, 1, 2, 3);
+        assert!(blinkt.show().unwrap());
+        assert!(!blinkt.show().unwrap());
+        assert_eq!(recording.len(), 1);
+
+        // Same pixels set again, still unchanged
+        blinkt.set_pixel(0, 1, 2, 3);
+        assert!(!blinkt.show().unwrap());
+        assert_eq!(recording.len(), 1);
+
+        blinkt.force_show().unwrap();
+        assert_eq!(recording.len(), 2);
+        assert_eq!(recording.frames()[0], recording.frames()[1]);
+
+        blinkt.set_pixel(1, 4, 5, 6);
+        assert!(blinkt.show().unwrap());
+        assert_eq!(recording.len(), 3);
+    }
+
+    #[test]
+    fn blinkt_recording_clear_on_drop() {
+        let output = RecordingOutput::new(1);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 1);
+        blinkt.set_all_pixels(1, 2, 3);
+        drop(blinkt);
+
+        assert_eq!(recording.len(), 1);
+        assert_eq!(recording.last().unwrap()[4..8], [0b1110_0111, 0, 0, 0]);
+    }
+
+    #[test]
+    fn blinkt_recording_max_frames() {
+        let output = RecordingOutput::new(1);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 1);
+        blinkt.set_clear_on_drop(false);
+        for value in 0..=MAX_FRAMES {
+            blinkt.set_pixel(0, u8::try_from(value % 256).unwrap(), 0, 0);
+            blinkt.show().unwrap();
+        }
+
+        assert_eq!(recording.len(), MAX_FRAMES);
+        // The first frame has been dropped
+        assert_eq!(recording.frames()[0][7], 1);
+    }
+}
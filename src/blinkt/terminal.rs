@@ -0,0 +1,134 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use super::{Pixel, Result, SerialOutput, decode::decode};
+use crate::word_art::paint_pixels;
+
+/// Minimum time between printed frames, so that fast animations stay readable
+const PREVIEW_INTERVAL_MS: u64 = 100;
+
+type Latest = Arc<Mutex<Option<Vec<Pixel>>>>;
+
+/// Output that doesn't need any hardware, frames are printed to the terminal as a row of coloured blocks.
+/// Only the latest frame is printed, at most once every `PREVIEW_INTERVAL_MS`, so the final frame of a burst is never lost.
+#[derive(Debug)]
+pub struct TerminalOutput {
+    current: Vec<u8>,
+    latest: Latest,
+    num_pixels: usize,
+}
+
+impl TerminalOutput {
+    /// Create the output, and spawn the thread that prints it, the thread exits once the output is dropped
+    pub fn new(num_pixels: usize) -> Self {
+        let latest = Latest::default();
+        let printer = Arc::clone(&latest);
+        std::thread::spawn(move || {
+            while Arc::strong_count(&printer) > 1 {
+                std::thread::sleep(Duration::from_millis(PREVIEW_INTERVAL_MS));
+                let pixels = printer
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .take();
+                if let Some(pixels) = pixels {
+                    println!("{}", Self::paint(&pixels));
+                }
+            }
+        });
+        Self {
+            current: vec![],
+            latest,
+            num_pixels,
+        }
+    }
+
+    /// Paint the pixels with their 5-bit brightness applied to each colour channel
+    fn paint(pixels: &[Pixel]) -> String {
+        let colours = pixels
+            .iter()
+            .map(|pixel| {
+                let (red, green, blue, brightness) = pixel.rgbb();
+                let shade = |value: u8| (f32::from(value) * brightness).round() as u8;
+                (shade(red), shade(green), shade(blue))
+            })
+            .collect::<Vec<_>>();
+        paint_pixels(&colours)
+    }
+}
+
+impl SerialOutput for TerminalOutput {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.current.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let frame = std::mem::take(&mut self.current);
+        match decode(&frame, self.num_pixels) {
+            Ok(decoded) => {
+                self.latest
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .replace(decoded.pixels);
+            }
+            Err(e) => tracing::warn!("Invalid frame: {e}"),
+        }
+        Ok(())
+    }
+}
+
+/// TerminalOutput tests
+///
+/// cargo watch -q -c -w src/ -x 'test blinkt_terminal -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::super::Blinkt;
+    use super::*;
+
+    #[test]
+    fn blinkt_terminal_paint() {
+        let mut full = Pixel::default();
+        full.set_rgbb(255, 128, 0, 1.0);
+        let mut dim = Pixel::default();
+        dim.set_rgbb(255, 128, 0, 0.5);
+        let off = Pixel::default();
+
+        assert_eq!(
+            TerminalOutput::paint(&[full, dim, off]),
+            paint_pixels(&[(255, 128, 0), (123, 62, 0), (0, 0, 0)])
+        );
+    }
+
+    #[test]
+    fn blinkt_terminal_latest() {
+        let output = TerminalOutput::new(2);
+        let latest = Arc::clone(&output.latest);
+        let mut blinkt = Blinkt::with_output(output, 2);
+        blinkt.set_clear_on_drop(false);
+
+        blinkt.set_pixel(0, 1, 2, 3);
+        blinkt.show().unwrap();
+        blinkt.set_pixel(0, 4, 5, 6);
+        blinkt.show().unwrap();
+
+        // Only the most recent frame is kept for printing
+        let pixels = latest.lock().unwrap().clone().unwrap();
+        assert_eq!(pixels[0].rgb(), (4, 5, 6));
+        assert_eq!(pixels[1].rgb(), (0, 0, 0));
+
+        std::thread::sleep(Duration::from_millis(PREVIEW_INTERVAL_MS * 2));
+        assert!(latest.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn blinkt_terminal_invalid_frame() {
+        let mut output = TerminalOutput::new(2);
+        output.write(&[0, 0, 0, 0, 0b1111_1111, 0, 0, 0]).unwrap();
+        output.flush().unwrap();
+        assert!(output.latest.lock().unwrap().is_none());
+        assert!(output.current.is_empty());
+    }
+}
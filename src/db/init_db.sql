@@ -1,5 +1,11 @@
 BEGIN;
 
+CREATE TABLE IF NOT EXISTS animation (
+	animation_id INTEGER PRIMARY KEY AUTOINCREMENT,
+	name TEXT NOT NULL UNIQUE,
+	keyframes TEXT NOT NULL
+) STRICT;
+
 CREATE TABLE IF NOT EXISTS alarm (
 	alarm_id INTEGER PRIMARY KEY AUTOINCREMENT,
 	day INTEGER NOT NULL CHECK (
@@ -14,12 +20,144 @@ CREATE TABLE IF NOT EXISTS alarm (
 		minute >= 0
 		AND minute <= 59
 	),
+	ramp_minutes INTEGER CHECK (
+		ramp_minutes >= 1
+		AND ramp_minutes <= 180
+	),
+	hold_minutes INTEGER CHECK (
+		hold_minutes >= 0
+		AND hold_minutes <= 180
+	),
+	mode TEXT NOT NULL DEFAULT 'start' CHECK (mode IN ('start', 'wake')),
+	animation_id INTEGER REFERENCES animation (animation_id) ON DELETE SET NULL,
+	escalation TEXT CHECK (escalation IN ('pulse', 'flash')),
+	escalation_minutes INTEGER CHECK (
+		escalation_minutes >= 1
+		AND escalation_minutes <= 60
+	),
+	UNIQUE (day, hour, minute)
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS active_alarm (
+	active_alarm_id INTEGER PRIMARY KEY CHECK (active_alarm_id = 1),
+	alarm_id INTEGER NOT NULL REFERENCES alarm (alarm_id) ON DELETE CASCADE,
+	started INTEGER NOT NULL,
+	snoozed_at INTEGER,
+	snooze_end INTEGER
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS alarm_outcome (
+	alarm_outcome_id INTEGER PRIMARY KEY AUTOINCREMENT,
+	alarm_id INTEGER REFERENCES alarm (alarm_id) ON DELETE SET NULL,
+	timestamp INTEGER NOT NULL,
+	outcome TEXT NOT NULL CHECK (outcome IN ('acknowledged', 'timed_out'))
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS sunset (
+	sunset_id INTEGER PRIMARY KEY AUTOINCREMENT,
+	day INTEGER NOT NULL CHECK (
+		day >= 0
+		AND day <= 6
+	),
+	hour INTEGER NOT NULL CHECK (
+		hour >= 0
+		AND hour <= 23
+	),
+	minute INTEGER NOT NULL CHECK (
+		minute >= 0
+		AND minute <= 59
+	),
+	brightness INTEGER CHECK (
+		brightness >= 1
+		AND brightness <= 100
+	),
+	colour TEXT,
+	minutes INTEGER CHECK (
+		minutes >= 1
+		AND minutes <= 180
+	),
 	UNIQUE (day, hour, minute)
 ) STRICT;
 
+CREATE TABLE IF NOT EXISTS scene (
+	scene_id INTEGER PRIMARY KEY AUTOINCREMENT,
+	name TEXT NOT NULL UNIQUE,
+	colour TEXT,
+	brightness INTEGER CHECK (
+		brightness >= 1
+		AND brightness <= 100
+	),
+	pixels TEXT,
+	minutes INTEGER CHECK (
+		minutes >= 1
+		AND minutes <= 720
+	)
+) STRICT;
+
 CREATE TABLE IF NOT EXISTS timezone (
 	timezone_id INTEGER PRIMARY KEY AUTOINCREMENT CHECK (timezone_id = 1),
 	zone_name TEXT NOT NULL
 ) STRICT;
 
+CREATE TABLE IF NOT EXISTS curve (
+	curve_id INTEGER PRIMARY KEY AUTOINCREMENT CHECK (curve_id = 1),
+	curve_name TEXT NOT NULL CHECK (
+		curve_name IN ('linear', 'exponential', 'gamma', 's_curve')
+	)
+) STRICT;
+
+INSERT OR IGNORE INTO curve (curve_id, curve_name) VALUES (1, 'linear');
+
+CREATE TABLE IF NOT EXISTS setting (
+	setting_id INTEGER PRIMARY KEY AUTOINCREMENT CHECK (setting_id = 1),
+	light_minutes INTEGER CHECK (
+		light_minutes >= 1
+		AND light_minutes <= 720
+	),
+	night_start INTEGER CHECK (
+		night_start >= 0
+		AND night_start <= 23
+	),
+	night_end INTEGER CHECK (
+		night_end >= 0
+		AND night_end <= 23
+	),
+	night_minutes INTEGER DEFAULT 15 CHECK (
+		night_minutes >= 1
+		AND night_minutes <= 720
+	)
+) STRICT;
+
+INSERT OR IGNORE INTO setting (setting_id, light_minutes) VALUES (1, 10);
+
+CREATE TABLE IF NOT EXISTS calibration (
+	calibration_id INTEGER PRIMARY KEY AUTOINCREMENT CHECK (calibration_id = 1),
+	red_gamma INTEGER NOT NULL DEFAULT 100 CHECK (
+		red_gamma >= 50
+		AND red_gamma <= 300
+	),
+	green_gamma INTEGER NOT NULL DEFAULT 100 CHECK (
+		green_gamma >= 50
+		AND green_gamma <= 300
+	),
+	blue_gamma INTEGER NOT NULL DEFAULT 100 CHECK (
+		blue_gamma >= 50
+		AND blue_gamma <= 300
+	),
+	white_red INTEGER NOT NULL DEFAULT 255 CHECK (
+		white_red >= 0
+		AND white_red <= 255
+	),
+	white_green INTEGER NOT NULL DEFAULT 255 CHECK (
+		white_green >= 0
+		AND white_green <= 255
+	),
+	white_blue INTEGER NOT NULL DEFAULT 255 CHECK (
+		white_blue >= 0
+		AND white_blue <= 255
+	)
+) STRICT;
+
+INSERT OR IGNORE INTO calibration (calibration_id) VALUES (1);
+
 COMMIT;
\ No newline at end of file
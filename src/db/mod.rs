@@ -1,10 +1,27 @@
+mod model_active_alarm;
 mod model_alarm;
+mod model_alarm_outcome;
+mod model_animation;
+mod model_calibration;
+mod model_curve;
+mod model_scene;
+mod model_setting;
+mod model_sunset;
 mod model_timezone;
 
-pub use model_alarm::ModelAlarm;
+pub use model_active_alarm::ModelActiveAlarm;
+pub use model_alarm::{AlarmMode, AlarmOptions, ModelAlarm};
+pub use model_alarm_outcome::{AlarmOutcome, ModelAlarmOutcome};
+pub use model_animation::{AnimationSummary, ModelAnimation};
+pub use model_calibration::ModelCalibration;
+pub use model_curve::ModelCurve;
+pub use model_scene::ModelScene;
+pub use model_setting::{ModelSetting, NightLightSetting};
+pub use model_sunset::ModelSunset;
 pub use model_timezone::ModelTimezone;
 
-use sqlx::{ConnectOptions, SqlitePool, sqlite::SqliteJournalMode};
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::{ConnectOptions, Row, SqlitePool, sqlite::SqliteJournalMode, sqlite::SqliteRow};
 use std::fs;
 
 use crate::app_env::AppEnv;
@@ -63,6 +80,22 @@ async fn get_db(app_envs: &AppEnv) -> Result<SqlitePool, sqlx::Error> {
     Ok(db)
 }
 
+/// Decode an optional json text column
+fn from_json<T: DeserializeOwned>(row: &SqliteRow, column: &str) -> Result<Option<T>, sqlx::Error> {
+    row.try_get::<Option<String>, _>(column)?
+        .map(|text| serde_json::from_str(&text))
+        .transpose()
+        .map_err(|e| sqlx::Error::ColumnDecode {
+            index: column.to_owned(),
+            source: Box::new(e),
+        })
+}
+
+/// Encode an optional value as json text
+fn to_json<T: Serialize>(value: Option<&T>) -> Option<String> {
+    value.and_then(|value| serde_json::to_string(value).ok())
+}
+
 /// Check if timezone in db, if not then insert
 async fn insert_env_timezone(db: &SqlitePool, app_envs: &AppEnv) {
     if ModelTimezone::get(db).await.is_none() {
@@ -72,6 +105,76 @@ async fn insert_env_timezone(db: &SqlitePool, app_envs: &AppEnv) {
     }
 }
 
+/// Columns added after a table was first created, as (table, column, definition)
+const ADDED_COLUMNS: [(&str, &str, &str); 10] = [
+    (
+        "alarm",
+        "ramp_minutes",
+        "INTEGER CHECK (ramp_minutes >= 1 AND ramp_minutes <= 180)",
+    ),
+    (
+        "alarm",
+        "hold_minutes",
+        "INTEGER CHECK (hold_minutes >= 0 AND hold_minutes <= 180)",
+    ),
+    (
+        "alarm",
+        "mode",
+        "TEXT NOT NULL DEFAULT 'start' CHECK (mode IN ('start', 'wake'))",
+    ),
+    (
+        "alarm",
+        "animation_id",
+        "INTEGER REFERENCES animation (animation_id) ON DELETE SET NULL",
+    ),
+    (
+        "alarm",
+        "escalation",
+        "TEXT CHECK (escalation IN ('pulse', 'flash'))",
+    ),
+    (
+        "alarm",
+        "escalation_minutes",
+        "INTEGER CHECK (escalation_minutes >= 1 AND escalation_minutes <= 60)",
+    ),
+    ("sunset", "colour", "TEXT"),
+    (
+        "setting",
+        "night_start",
+        "INTEGER CHECK (night_start >= 0 AND night_start <= 23)",
+    ),
+    (
+        "setting",
+        "night_end",
+        "INTEGER CHECK (night_end >= 0 AND night_end <= 23)",
+    ),
+    (
+        "setting",
+        "night_minutes",
+        "INTEGER DEFAULT 15 CHECK (night_minutes >= 1 AND night_minutes <= 720)",
+    ),
+];
+
+/// Add any missing columns to tables created by an older version of init_db.sql
+async fn add_missing_columns(db: &SqlitePool) {
+    for (table, column, definition) in ADDED_COLUMNS {
+        let sql = "SELECT COUNT(*) FROM pragma_table_info($1) WHERE name = $2";
+        let exists = sqlx::query_scalar::<_, i64>(sql)
+            .bind(table)
+            .bind(column)
+            .fetch_one(db)
+            .await
+            .is_ok_and(|count| count > 0);
+        if !exists {
+            let sql = format!("ALTER TABLE {table} ADD COLUMN {column} {definition}");
+            if let Err(e) = sqlx::query(sqlx::AssertSqlSafe(sql)).execute(db).await {
+                tracing::error!("add_column::{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
 async fn create_tables(db: &SqlitePool) {
     let init_db = include_str!("init_db.sql");
     match sqlx::query(init_db).execute(db).await {
@@ -88,6 +191,7 @@ pub async fn init_db(app_envs: &AppEnv) -> Result<SqlitePool, sqlx::Error> {
     file_exists(&app_envs.location_sqlite);
     let db = get_db(app_envs).await?;
     create_tables(&db).await;
+    add_missing_columns(&db).await;
     insert_env_timezone(&db, app_envs).await;
     Ok(db)
 }
@@ -146,6 +250,53 @@ mod tests {
         test_cleanup(uuid, None).await;
     }
 
+    #[tokio::test]
+    async fn sql_mod_add_missing_columns() {
+        let uuid = uuid::Uuid::new_v4();
+        let args = gen_app_envs(uuid);
+        file_exists(&args.location_sqlite);
+        let db = get_db(&args).await.unwrap();
+        sqlx::query("CREATE TABLE alarm (alarm_id INTEGER PRIMARY KEY AUTOINCREMENT, day INTEGER NOT NULL, hour INTEGER NOT NULL, minute INTEGER NOT NULL) STRICT")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO alarm (day, hour, minute) VALUES (1, 6, 30)")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE setting (setting_id INTEGER PRIMARY KEY AUTOINCREMENT CHECK (setting_id = 1), light_minutes INTEGER) STRICT")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO setting (setting_id, light_minutes) VALUES (1, 30)")
+            .execute(&db)
+            .await
+            .unwrap();
+        db.close().await;
+
+        let db = init_db(&args).await.unwrap();
+        let result = ModelAlarm::get_all(&db).await.unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].hour, 6);
+        assert!(result[0].ramp_minutes.is_none());
+        assert!(result[0].hold_minutes.is_none());
+        assert_eq!(result[0].mode, AlarmMode::Start);
+        assert!(result[0].animation_id.is_none());
+        assert!(result[0].escalation.is_none());
+
+        let result = ModelSetting::get(&db).await.unwrap();
+        assert_eq!(result.light_minutes, Some(30));
+        assert!(result.night_start.is_none());
+        assert_eq!(result.night_minutes, Some(15));
+
+        // Running again leaves the table as is
+        add_missing_columns(&db).await;
+        assert_eq!(ModelAlarm::get_all(&db).await.unwrap().len(), 1);
+
+        test_cleanup(uuid, Some(db)).await;
+    }
+
     #[tokio::test]
     async fn sql_mod_db_created_with_timezone() {
         let uuid = uuid::Uuid::new_v4();
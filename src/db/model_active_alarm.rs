@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::{fmt, time::Duration};
+
+use crate::app_error::AppError;
+
+/// The alarm sequence currently in progress, kept so that it can be resumed after a restart
+#[derive(
+    sqlx::FromRow, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct ModelActiveAlarm {
+    pub alarm_id: i64,
+    /// Unix timestamp, in seconds, of when the sequence started, moved forward by any time spent snoozing
+    pub started: i64,
+    /// Unix timestamp, in seconds, of when the current snooze started, `None` when not snoozed
+    pub snoozed_at: Option<i64>,
+    /// Unix timestamp, in seconds, of when the current snooze ends, `None` when not snoozed
+    pub snooze_end: Option<i64>,
+}
+
+impl fmt::Display for ModelActiveAlarm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "alarm_id: {}, started:{}, snoozed_at:{:?}, snooze_end:{:?}",
+            self.alarm_id, self.started, self.snoozed_at, self.snooze_end
+        )
+    }
+}
+
+impl ModelActiveAlarm {
+    /// Time since the sequence started, excluding any time spent snoozing
+    /// A snooze that ended whilst the app was stopped counts as if the sequence was resumed at the end of the snooze
+    pub fn elapsed(&self) -> Duration {
+        let now = jiff::Timestamp::now().as_second();
+        let elapsed = match (self.snoozed_at, self.snooze_end) {
+            (Some(snoozed_at), Some(snooze_end)) => {
+                snoozed_at - self.started + (now - snooze_end).max(0)
+            }
+            _ => now - self.started,
+        };
+        Duration::from_secs(u64::try_from(elapsed).unwrap_or_default())
+    }
+
+    /// Time left until the snooze ends, `None` if not snoozed, or the snooze has already ended
+    pub fn snooze_remaining(&self) -> Option<Duration> {
+        let now = jiff::Timestamp::now().as_second();
+        self.snooze_end
+            .filter(|snooze_end| *snooze_end > now)
+            .and_then(|snooze_end| u64::try_from(snooze_end - now).ok())
+            .map(Duration::from_secs)
+    }
+
+    pub async fn get(db: &SqlitePool) -> Option<Self> {
+        let sql = "SELECT alarm_id, started, snoozed_at, snooze_end FROM active_alarm";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_optional(db).await;
+        result.ok().flatten()
+    }
+
+    /// Set the active alarm, `elapsed` is the time since the sequence started, and `snooze` the time left of a snooze in progress
+    pub async fn set(
+        db: &SqlitePool,
+        alarm_id: i64,
+        elapsed: Duration,
+        snooze: Option<Duration>,
+    ) -> Result<Self, AppError> {
+        let now = jiff::Timestamp::now().as_second();
+        let as_seconds = |duration: Duration| i64::try_from(duration.as_secs()).unwrap_or_default();
+        let started = now - as_seconds(elapsed);
+        let sql = "INSERT OR REPLACE INTO active_alarm(active_alarm_id, alarm_id, started, snoozed_at, snooze_end) VALUES (1, $1, $2, $3, $4) RETURNING alarm_id, started, snoozed_at, snooze_end";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(alarm_id)
+            .bind(started)
+            .bind(snooze.map(|_| now))
+            .bind(snooze.map(|snooze| now + as_seconds(snooze)))
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+
+    pub async fn delete(db: &SqlitePool) -> Result<(), AppError> {
+        let sql = "DELETE FROM active_alarm";
+        sqlx::query(sql).execute(db).await?;
+        Ok(())
+    }
+}
+
+/// ModelActiveAlarm tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_active_alarm -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::{
+        db::{AlarmOptions, ModelAlarm},
+        tests::{test_cleanup, test_setup},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn model_active_alarm_set_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &AlarmOptions::default())
+            .await
+            .unwrap();
+        assert!(ModelActiveAlarm::get(&db).await.is_none());
+
+        let result =
+            ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::from_secs(600), None).await;
+
+        assert!(result.is_ok());
+        let result = ModelActiveAlarm::get(&db).await.unwrap();
+        assert_eq!(result.alarm_id, alarm.alarm_id);
+        let elapsed = result.elapsed();
+        assert!(elapsed >= Duration::from_secs(600) && elapsed < Duration::from_secs(602));
+
+        // Only a single active alarm
+        ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::from_secs(1200), None)
+            .await
+            .unwrap();
+        let elapsed = ModelActiveAlarm::get(&db).await.unwrap().elapsed();
+        assert!(elapsed >= Duration::from_secs(1200) && elapsed < Duration::from_secs(1202));
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_active_alarm_set_snoozed_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &AlarmOptions::default())
+            .await
+            .unwrap();
+
+        ModelActiveAlarm::set(
+            &db,
+            alarm.alarm_id,
+            Duration::from_secs(600),
+            Some(Duration::from_secs(300)),
+        )
+        .await
+        .unwrap();
+
+        let result = ModelActiveAlarm::get(&db).await.unwrap();
+        let elapsed = result.elapsed();
+        assert!(elapsed >= Duration::from_secs(600) && elapsed < Duration::from_secs(602));
+        let remaining = result.snooze_remaining().unwrap();
+        assert!(remaining > Duration::from_secs(297) && remaining <= Duration::from_secs(300));
+
+        // Progress saved once the snooze has ended clears it
+        ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::from_secs(600), None)
+            .await
+            .unwrap();
+        let result = ModelActiveAlarm::get(&db).await.unwrap();
+        assert!(result.snoozed_at.is_none());
+        assert!(result.snooze_remaining().is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[test]
+    fn model_active_alarm_snooze_ended() {
+        let now = jiff::Timestamp::now().as_second();
+        // Snoozed 100 seconds into the sequence, and the snooze ended a minute ago
+        let active = ModelActiveAlarm {
+            alarm_id: 1,
+            started: now - 1000,
+            snoozed_at: Some(now - 900),
+            snooze_end: Some(now - 60),
+        };
+
+        assert!(active.snooze_remaining().is_none());
+        let elapsed = active.elapsed();
+        assert!(elapsed >= Duration::from_secs(160) && elapsed < Duration::from_secs(162));
+    }
+
+    #[tokio::test]
+    async fn model_active_alarm_delete_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &AlarmOptions::default())
+            .await
+            .unwrap();
+        ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::ZERO, None)
+            .await
+            .unwrap();
+
+        ModelActiveAlarm::delete(&db).await.unwrap();
+
+        assert!(ModelActiveAlarm::get(&db).await.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_active_alarm_alarm_deleted() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &AlarmOptions::default())
+            .await
+            .unwrap();
+        ModelActiveAlarm::set(&db, alarm.alarm_id, Duration::ZERO, None)
+            .await
+            .unwrap();
+
+        ModelAlarm::delete(&db, alarm.alarm_id).await.unwrap();
+
+        assert!(ModelActiveAlarm::get(&db).await.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_active_alarm_unknown_alarm_err() {
+        let (_app_env, db, uuid) = test_setup().await;
+
+        let result = ModelActiveAlarm::set(&db, 4, Duration::ZERO, None).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
@@ -2,7 +2,38 @@ use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use std::fmt;
 
-use crate::app_error::AppError;
+use crate::{
+    app_error::AppError,
+    light::{Escalation, RAMP_MINUTES},
+};
+
+const MINUTES_IN_DAY: i64 = 24 * 60;
+const MINUTES_IN_WEEK: i64 = 7 * MINUTES_IN_DAY;
+
+/// What the stored alarm time represents
+#[derive(
+    sqlx::Type,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum AlarmMode {
+    /// The sequence starts at the alarm time
+    #[default]
+    Start,
+    /// The sequence reaches full brightness at the alarm time
+    Wake,
+}
 
 #[derive(
     sqlx::FromRow, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
@@ -12,31 +43,88 @@ pub struct ModelAlarm {
     pub day: i8,
     pub hour: i8,
     pub minute: i8,
+    pub ramp_minutes: Option<u16>,
+    pub hold_minutes: Option<u16>,
+    pub mode: AlarmMode,
+    /// Animation used as the sunrise profile
+    pub animation_id: Option<i64>,
+    /// What happens once the hold has finished, if the alarm hasn't been dismissed
+    pub escalation: Option<Escalation>,
+    pub escalation_minutes: Option<u16>,
+}
+
+/// Optional per alarm settings, `None` values use the light control defaults
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmOptions {
+    pub ramp_minutes: Option<u16>,
+    pub hold_minutes: Option<u16>,
+    pub mode: AlarmMode,
+    pub animation_id: Option<i64>,
+    pub escalation: Option<Escalation>,
+    pub escalation_minutes: Option<u16>,
 }
 
 impl fmt::Display for ModelAlarm {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "alarm_id: {}, day:{}, hour:{}, minute:{}",
-            self.alarm_id, self.day, self.hour, self.minute
+            "alarm_id: {}, day:{}, hour:{}, minute:{}, ramp_minutes:{:?}, hold_minutes:{:?}, mode:{:?}, animation_id:{:?}, escalation:{:?}, escalation_minutes:{:?}",
+            self.alarm_id,
+            self.day,
+            self.hour,
+            self.minute,
+            self.ramp_minutes,
+            self.hold_minutes,
+            self.mode,
+            self.animation_id,
+            self.escalation,
+            self.escalation_minutes
         )
     }
 }
 
 impl ModelAlarm {
+    /// The (day, hour, minute) at which the alarm sequence should start.
+    /// Wake alarms start early by the ramp length, which can cross midnight into the previous day
+    pub fn start_time(&self) -> (i8, i8, i8) {
+        let lead = match self.mode {
+            AlarmMode::Start => 0,
+            AlarmMode::Wake => i64::from(self.ramp_minutes.unwrap_or(RAMP_MINUTES)),
+        };
+        let start = (i64::from(self.day) * MINUTES_IN_DAY
+            + i64::from(self.hour) * 60
+            + i64::from(self.minute)
+            - lead)
+            .rem_euclid(MINUTES_IN_WEEK);
+        (
+            i8::try_from(start / MINUTES_IN_DAY).unwrap_or_default(),
+            i8::try_from(start % MINUTES_IN_DAY / 60).unwrap_or_default(),
+            i8::try_from(start % 60).unwrap_or_default(),
+        )
+    }
+
     pub async fn get_all(db: &SqlitePool) -> Result<Vec<Self>, AppError> {
         let sql = "SELECT * FROM alarm";
         let result = sqlx::query_as::<_, Self>(sql).fetch_all(db).await?;
         Ok(result)
     }
 
-    pub async fn add(db: &SqlitePool, data: (u8, u8, u8)) -> Result<Self, AppError> {
-        let sql = "INSERT INTO alarm(day, hour, minute) VALUES ($1, $2, $3) RETURNING alarm_id, day, hour, minute";
+    pub async fn add(
+        db: &SqlitePool,
+        data: (u8, u8, u8),
+        options: &AlarmOptions,
+    ) -> Result<Self, AppError> {
+        let sql = "INSERT INTO alarm(day, hour, minute, ramp_minutes, hold_minutes, mode, animation_id, escalation, escalation_minutes) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING *";
         let query = sqlx::query_as::<_, Self>(sql)
             .bind(data.0)
             .bind(data.1)
             .bind(data.2)
+            .bind(options.ramp_minutes)
+            .bind(options.hold_minutes)
+            .bind(options.mode)
+            .bind(options.animation_id)
+            .bind(options.escalation)
+            .bind(options.escalation_minutes)
             .fetch_one(db)
             .await?;
         Ok(query)
@@ -70,7 +158,7 @@ mod tests {
         let (_app_env, db, uuid) = test_setup().await;
         let data = (1, 10, 10);
 
-        let result = ModelAlarm::add(&db, data).await;
+        let result = ModelAlarm::add(&db, data, &AlarmOptions::default()).await;
 
         assert!(result.is_ok());
         let result = result.unwrap();
@@ -87,7 +175,7 @@ mod tests {
         let (_app_env, db, uuid) = test_setup().await;
         let data = (10, 10, 10);
 
-        let result = ModelAlarm::add(&db, data).await;
+        let result = ModelAlarm::add(&db, data, &AlarmOptions::default()).await;
 
         assert!(result.is_err());
         assert_eq!(
@@ -102,7 +190,7 @@ mod tests {
         let (_app_env, db, uuid) = test_setup().await;
         let data = (1, 25, 10);
 
-        let result = ModelAlarm::add(&db, data).await;
+        let result = ModelAlarm::add(&db, data, &AlarmOptions::default()).await;
 
         assert!(result.is_err());
         assert_eq!(
@@ -117,7 +205,7 @@ mod tests {
         let (_app_env, db, uuid) = test_setup().await;
         let data = (1, 10, 60);
 
-        let result = ModelAlarm::add(&db, data).await;
+        let result = ModelAlarm::add(&db, data, &AlarmOptions::default()).await;
 
         assert!(result.is_err());
         assert_eq!(
@@ -127,12 +215,108 @@ mod tests {
         test_cleanup(uuid, Some(db)).await;
     }
 
+    #[tokio::test]
+    async fn model_alarm_add_with_options_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let data = (1, 10, 10);
+        let options = AlarmOptions {
+            ramp_minutes: Some(20),
+            hold_minutes: Some(0),
+            mode: AlarmMode::Wake,
+            animation_id: None,
+            escalation: Some(Escalation::Pulse),
+            escalation_minutes: Some(30),
+        };
+
+        let result = ModelAlarm::add(&db, data, &options).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.ramp_minutes, Some(20));
+        assert_eq!(result.hold_minutes, Some(0));
+        assert_eq!(result.mode, AlarmMode::Wake);
+        assert_eq!(result.escalation, Some(Escalation::Pulse));
+        assert_eq!(result.escalation_minutes, Some(30));
+
+        let result = ModelAlarm::add(&db, (2, 10, 10), &AlarmOptions::default())
+            .await
+            .unwrap();
+        assert!(result.ramp_minutes.is_none());
+        assert!(result.hold_minutes.is_none());
+        assert_eq!(result.mode, AlarmMode::Start);
+        assert!(result.escalation.is_none());
+        assert!(result.escalation_minutes.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_alarm_add_err_invalid_ramp() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let options = AlarmOptions {
+            ramp_minutes: Some(0),
+            ..AlarmOptions::default()
+        };
+
+        let result = ModelAlarm::add(&db, (1, 10, 10), &options).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    fn gen_alarm(
+        day: i8,
+        hour: i8,
+        minute: i8,
+        ramp_minutes: Option<u16>,
+        mode: AlarmMode,
+    ) -> ModelAlarm {
+        ModelAlarm {
+            alarm_id: 1,
+            day,
+            hour,
+            minute,
+            ramp_minutes,
+            hold_minutes: None,
+            mode,
+            animation_id: None,
+            escalation: None,
+            escalation_minutes: None,
+        }
+    }
+
+    #[test]
+    fn model_alarm_start_time_start_mode() {
+        let alarm = gen_alarm(2, 6, 30, Some(60), AlarmMode::Start);
+        assert_eq!(alarm.start_time(), (2, 6, 30));
+    }
+
+    #[test]
+    fn model_alarm_start_time_wake_mode() {
+        let alarm = gen_alarm(2, 6, 30, Some(20), AlarmMode::Wake);
+        assert_eq!(alarm.start_time(), (2, 6, 10));
+
+        let alarm = gen_alarm(2, 7, 0, None, AlarmMode::Wake);
+        assert_eq!(alarm.start_time(), (2, 5, 30));
+    }
+
+    #[test]
+    fn model_alarm_start_time_previous_day() {
+        let alarm = gen_alarm(3, 0, 30, Some(60), AlarmMode::Wake);
+        assert_eq!(alarm.start_time(), (2, 23, 30));
+
+        // Monday wraps back to Sunday
+        let alarm = gen_alarm(0, 0, 10, Some(20), AlarmMode::Wake);
+        assert_eq!(alarm.start_time(), (6, 23, 50));
+    }
+
     #[tokio::test]
     async fn model_alarm_get_all_ok() {
         let (_app_env, db, uuid) = test_setup().await;
         for i in 0..6 {
             let data = (i, i, i);
-            ModelAlarm::add(&db, data).await.unwrap();
+            ModelAlarm::add(&db, data, &AlarmOptions::default())
+                .await
+                .unwrap();
         }
 
         let result = ModelAlarm::get_all(&db).await;
@@ -150,7 +334,9 @@ mod tests {
     async fn model_alarm_delete_one_ok() {
         let (_app_env, db, uuid) = test_setup().await;
         let data = (1, 10, 10);
-        let alarm = ModelAlarm::add(&db, data).await.unwrap();
+        let alarm = ModelAlarm::add(&db, data, &AlarmOptions::default())
+            .await
+            .unwrap();
 
         let result = ModelAlarm::delete(&db, alarm.alarm_id).await;
         let alarm = ModelAlarm::get_all(&db).await.unwrap();
@@ -165,7 +351,9 @@ mod tests {
         let (_app_env, db, uuid) = test_setup().await;
         for i in 0..6 {
             let data = (i, i, i);
-            ModelAlarm::add(&db, data).await.unwrap();
+            ModelAlarm::add(&db, data, &AlarmOptions::default())
+                .await
+                .unwrap();
         }
 
         let result = ModelAlarm::delete(&db, 1).await;
@@ -181,7 +369,9 @@ mod tests {
         let (_app_env, db, uuid) = test_setup().await;
         for i in 0..6 {
             let data = (i, i, i);
-            ModelAlarm::add(&db, data).await.unwrap();
+            ModelAlarm::add(&db, data, &AlarmOptions::default())
+                .await
+                .unwrap();
         }
 
         let result = ModelAlarm::delete_all(&db).await;
@@ -196,7 +386,9 @@ mod tests {
     async fn model_alarm_delete_err() {
         let (_app_env, db, uuid) = test_setup().await;
         let data = (1, 10, 10);
-        ModelAlarm::add(&db, data).await.unwrap();
+        ModelAlarm::add(&db, data, &AlarmOptions::default())
+            .await
+            .unwrap();
 
         let result = ModelAlarm::delete(&db, 2).await;
         let alarm = ModelAlarm::get_all(&db).await.unwrap();
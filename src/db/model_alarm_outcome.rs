@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fmt;
+
+use crate::app_error::AppError;
+
+/// Number of outcomes kept, older outcomes are removed when a new one is added
+const MAX_OUTCOMES: i64 = 100;
+
+/// How an alarm sequence ended
+#[derive(
+    sqlx::Type, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum AlarmOutcome {
+    /// Dismissed, or the light was changed, whilst the alarm was in progress
+    Acknowledged,
+    /// The alarm ran until the end, without any interaction
+    TimedOut,
+}
+
+#[derive(
+    sqlx::FromRow, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct ModelAlarmOutcome {
+    pub alarm_outcome_id: i64,
+    /// `None` when the alarm has since been deleted
+    pub alarm_id: Option<i64>,
+    /// Unix timestamp, in seconds, of when the alarm ended
+    pub timestamp: i64,
+    pub outcome: AlarmOutcome,
+}
+
+impl fmt::Display for ModelAlarmOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "alarm_outcome_id: {}, alarm_id:{:?}, timestamp:{}, outcome:{:?}",
+            self.alarm_outcome_id, self.alarm_id, self.timestamp, self.outcome
+        )
+    }
+}
+
+impl ModelAlarmOutcome {
+    /// Most recent outcomes first
+    pub async fn get_recent(db: &SqlitePool, limit: i64) -> Result<Vec<Self>, AppError> {
+        let sql = "SELECT * FROM alarm_outcome ORDER BY alarm_outcome_id DESC LIMIT $1";
+        let result = sqlx::query_as::<_, Self>(sql)
+            .bind(limit)
+            .fetch_all(db)
+            .await?;
+        Ok(result)
+    }
+
+    /// Record how an alarm ended, the alarm id is `None` when the sequence wasn't started from a stored alarm
+    pub async fn add(
+        db: &SqlitePool,
+        alarm_id: Option<i64>,
+        outcome: AlarmOutcome,
+    ) -> Result<Self, AppError> {
+        let sql = "INSERT INTO alarm_outcome(alarm_id, timestamp, outcome) VALUES ($1, unixepoch(), $2) RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(alarm_id)
+            .bind(outcome)
+            .fetch_one(db)
+            .await?;
+        let sql = "DELETE FROM alarm_outcome WHERE alarm_outcome_id <= $1";
+        sqlx::query(sql)
+            .bind(query.alarm_outcome_id - MAX_OUTCOMES)
+            .execute(db)
+            .await?;
+        Ok(query)
+    }
+}
+
+/// ModelAlarmOutcome tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_alarm_outcome -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::{
+        db::{AlarmOptions, ModelAlarm},
+        tests::{test_cleanup, test_setup},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn model_alarm_outcome_add_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let alarm = ModelAlarm::add(&db, (1, 6, 30), &AlarmOptions::default())
+            .await
+            .unwrap();
+
+        let result =
+            ModelAlarmOutcome::add(&db, Some(alarm.alarm_id), AlarmOutcome::TimedOut).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.alarm_id, Some(alarm.alarm_id));
+        assert_eq!(result.outcome, AlarmOutcome::TimedOut);
+        assert!(result.timestamp > 0);
+
+        // Kept after the alarm is deleted
+        ModelAlarm::delete(&db, alarm.alarm_id).await.unwrap();
+        let result = ModelAlarmOutcome::get_recent(&db, 10).await.unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].alarm_id.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_alarm_outcome_get_recent() {
+        let (_app_env, db, uuid) = test_setup().await;
+        ModelAlarmOutcome::add(&db, None, AlarmOutcome::TimedOut)
+            .await
+            .unwrap();
+        ModelAlarmOutcome::add(&db, None, AlarmOutcome::Acknowledged)
+            .await
+            .unwrap();
+
+        let result = ModelAlarmOutcome::get_recent(&db, 1).await.unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].outcome, AlarmOutcome::Acknowledged);
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_alarm_outcome_max() {
+        let (_app_env, db, uuid) = test_setup().await;
+        for _ in 0..MAX_OUTCOMES + 5 {
+            ModelAlarmOutcome::add(&db, None, AlarmOutcome::Acknowledged)
+                .await
+                .unwrap();
+        }
+
+        let result = ModelAlarmOutcome::get_recent(&db, MAX_OUTCOMES * 2)
+            .await
+            .unwrap();
+        assert_eq!(i64::try_from(result.len()).unwrap(), MAX_OUTCOMES);
+        assert_eq!(result[0].alarm_outcome_id, MAX_OUTCOMES + 5);
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
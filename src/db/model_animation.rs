@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+use std::fmt;
+
+use super::{from_json, to_json};
+use crate::{app_error::AppError, light::Animation};
+
+/// A named keyframe animation, the keyframes are stored as json text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModelAnimation {
+    pub animation_id: i64,
+    pub name: String,
+    pub animation: Animation,
+}
+
+/// A stored animation without its keyframes, small enough to be included in every pi status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AnimationSummary {
+    pub animation_id: i64,
+    pub name: String,
+    /// Time from the first, to the last, keyframe
+    pub duration_ms: u64,
+}
+
+impl<'r> sqlx::FromRow<'r, SqliteRow> for ModelAnimation {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            animation_id: row.try_get("animation_id")?,
+            name: row.try_get("name")?,
+            animation: from_json(row, "keyframes")?.ok_or_else(|| sqlx::Error::ColumnDecode {
+                index: "keyframes".to_owned(),
+                source: "no keyframes".into(),
+            })?,
+        })
+    }
+}
+
+impl fmt::Display for ModelAnimation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "animation_id: {}, name:{}, duration:{:?}",
+            self.animation_id,
+            self.name,
+            self.animation.duration()
+        )
+    }
+}
+
+impl ModelAnimation {
+    pub fn summary(&self) -> AnimationSummary {
+        AnimationSummary {
+            animation_id: self.animation_id,
+            name: self.name.clone(),
+            duration_ms: u64::try_from(self.animation.duration().as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+
+    pub async fn get(db: &SqlitePool, id: i64) -> Result<Option<Self>, AppError> {
+        let sql = "SELECT * FROM animation WHERE animation_id = $1";
+        let result = sqlx::query_as::<_, Self>(sql)
+            .bind(id)
+            .fetch_optional(db)
+            .await?;
+        Ok(result)
+    }
+
+    pub async fn get_all(db: &SqlitePool) -> Result<Vec<Self>, AppError> {
+        let sql = "SELECT * FROM animation ORDER BY name";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_all(db).await?;
+        Ok(result)
+    }
+
+    pub async fn add(db: &SqlitePool, name: &str, animation: &Animation) -> Result<Self, AppError> {
+        let sql = "INSERT INTO animation(name, keyframes) VALUES ($1, $2) RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(name)
+            .bind(to_json(Some(animation)))
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+
+    /// Delete an animation, any alarm using it as a sunrise profile goes back to the default sunrise
+    pub async fn delete(db: &SqlitePool, id: i64) -> Result<(), AppError> {
+        let sql = "DELETE FROM animation WHERE animation_id = $1";
+        sqlx::query(sql).bind(id).execute(db).await?;
+        Ok(())
+    }
+}
+
+/// ModelAnimation tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_animation -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::{
+        db::{AlarmOptions, ModelAlarm},
+        tests::{test_cleanup, test_setup},
+    };
+
+    use super::*;
+
+    fn gen_animation() -> Animation {
+        serde_json::from_str(
+            r#"[{"offset_ms":0,"pixels":[[255,0,0]],"brightness":10},{"offset_ms":2000,"pixels":[[0,0,255]],"brightness":80,"easing":"ease_out"}]"#,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn model_animation_add_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+
+        let result = ModelAnimation::add(&db, "aurora", &gen_animation()).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.animation_id, 1);
+        assert_eq!(result.name, "aurora");
+        assert_eq!(result.animation, gen_animation());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_animation_add_err_duplicate() {
+        let (_app_env, db, uuid) = test_setup().await;
+        ModelAnimation::add(&db, "aurora", &gen_animation())
+            .await
+            .unwrap();
+
+        let result = ModelAnimation::add(&db, "aurora", &gen_animation()).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_animation_get_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        ModelAnimation::add(&db, "aurora", &gen_animation())
+            .await
+            .unwrap();
+
+        let result = ModelAnimation::get(&db, 1).await.unwrap();
+        assert_eq!(result.unwrap().animation, gen_animation());
+        assert!(ModelAnimation::get(&db, 2).await.unwrap().is_none());
+
+        let result = ModelAnimation::get_all(&db).await.unwrap();
+        assert_eq!(result.len(), 1);
+
+        let summary = result[0].summary();
+        assert_eq!(summary.animation_id, 1);
+        assert_eq!(summary.name, "aurora");
+        assert_eq!(summary.duration_ms, 2000);
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_animation_delete_clears_alarm_profile() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let animation = ModelAnimation::add(&db, "aurora", &gen_animation())
+            .await
+            .unwrap();
+        let options = AlarmOptions {
+            animation_id: Some(animation.animation_id),
+            ..AlarmOptions::default()
+        };
+        ModelAlarm::add(&db, (1, 6, 30), &options).await.unwrap();
+        assert_eq!(
+            ModelAlarm::get_all(&db).await.unwrap()[0].animation_id,
+            Some(animation.animation_id)
+        );
+
+        ModelAnimation::delete(&db, animation.animation_id)
+            .await
+            .unwrap();
+
+        assert!(ModelAnimation::get_all(&db).await.unwrap().is_empty());
+        assert!(
+            ModelAlarm::get_all(&db).await.unwrap()[0]
+                .animation_id
+                .is_none()
+        );
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_animation_alarm_unknown_profile_err() {
+        let (_app_env, db, uuid) = test_setup().await;
+        let options = AlarmOptions {
+            animation_id: Some(3),
+            ..AlarmOptions::default()
+        };
+
+        let result = ModelAlarm::add(&db, (1, 6, 30), &options).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
@@ -0,0 +1,133 @@
+use sqlx::SqlitePool;
+use std::fmt;
+
+use crate::{app_error::AppError, blinkt::CalibrationSettings};
+
+#[derive(sqlx::FromRow, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ModelCalibration {
+    pub calibration_id: i64,
+    /// Gamma, in hundredths, for each channel
+    pub red_gamma: u16,
+    pub green_gamma: u16,
+    pub blue_gamma: u16,
+    /// The value full white is scaled to, for each channel
+    pub white_red: u8,
+    pub white_green: u8,
+    pub white_blue: u8,
+}
+
+impl fmt::Display for ModelCalibration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "calibration_id: {}, gamma: ({}, {}, {}), white_point: ({}, {}, {})",
+            self.calibration_id,
+            self.red_gamma,
+            self.green_gamma,
+            self.blue_gamma,
+            self.white_red,
+            self.white_green,
+            self.white_blue,
+        )
+    }
+}
+
+impl Default for ModelCalibration {
+    fn default() -> Self {
+        let settings = CalibrationSettings::default();
+        Self {
+            calibration_id: 1,
+            red_gamma: settings.gamma.0,
+            green_gamma: settings.gamma.1,
+            blue_gamma: settings.gamma.2,
+            white_red: settings.white_point.0,
+            white_green: settings.white_point.1,
+            white_blue: settings.white_point.2,
+        }
+    }
+}
+
+impl ModelCalibration {
+    pub const fn settings(&self) -> CalibrationSettings {
+        CalibrationSettings {
+            gamma: (self.red_gamma, self.green_gamma, self.blue_gamma),
+            white_point: (self.white_red, self.white_green, self.white_blue),
+        }
+    }
+
+    pub async fn get(db: &SqlitePool) -> Option<Self> {
+        let sql = "SELECT * FROM calibration";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_one(db).await;
+        result.ok()
+    }
+
+    pub async fn update(db: &SqlitePool, settings: &CalibrationSettings) -> Result<Self, AppError> {
+        let sql = "UPDATE calibration SET red_gamma = $1, green_gamma = $2, blue_gamma = $3, white_red = $4, white_green = $5, white_blue = $6 RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(settings.gamma.0)
+            .bind(settings.gamma.1)
+            .bind(settings.gamma.2)
+            .bind(settings.white_point.0)
+            .bind(settings.white_point.1)
+            .bind(settings.white_point.2)
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+}
+
+/// ModelCalibration tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_calibration -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::tests::{test_cleanup, test_setup};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn model_calibration_get_ok_with_init() {
+        let (_, db, uuid) = test_setup().await;
+        let result = ModelCalibration::get(&db).await;
+
+        assert!(result.is_some());
+        let result = result.unwrap();
+        assert_eq!(result, ModelCalibration::default());
+        assert_eq!(result.settings(), CalibrationSettings::default());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_calibration_update_ok() {
+        let (_, db, uuid) = test_setup().await;
+        let settings = CalibrationSettings {
+            gamma: (220, 200, 210),
+            white_point: (255, 190, 170),
+        };
+
+        let result = ModelCalibration::update(&db, &settings).await;
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().settings(), settings);
+        assert_eq!(
+            ModelCalibration::get(&db).await.unwrap().settings(),
+            settings
+        );
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_calibration_update_err_invalid_gamma() {
+        let (_, db, uuid) = test_setup().await;
+        let settings = CalibrationSettings {
+            gamma: (10, 100, 100),
+            ..CalibrationSettings::default()
+        };
+
+        let result = ModelCalibration::update(&db, &settings).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
@@ -0,0 +1,100 @@
+use sqlx::SqlitePool;
+use std::fmt;
+
+use crate::{app_error::AppError, light::BrightnessCurve};
+
+#[derive(sqlx::FromRow, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ModelCurve {
+    pub curve_id: i64,
+    pub curve_name: BrightnessCurve,
+}
+
+impl fmt::Display for ModelCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "curve_id: {}, curve_name: {:?}",
+            self.curve_id, self.curve_name,
+        )
+    }
+}
+
+impl Default for ModelCurve {
+    fn default() -> Self {
+        Self {
+            curve_id: 1,
+            curve_name: BrightnessCurve::default(),
+        }
+    }
+}
+
+impl ModelCurve {
+    pub async fn get(db: &SqlitePool) -> Option<Self> {
+        let sql = "SELECT * FROM curve";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_one(db).await;
+        result.ok()
+    }
+
+    pub async fn update(db: &SqlitePool, curve: BrightnessCurve) -> Result<Self, AppError> {
+        let sql = "UPDATE curve SET curve_name = $1 RETURNING curve_id, curve_name";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(curve)
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+}
+
+/// ModelCurve tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_curve -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::tests::{test_cleanup, test_setup};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn model_curve_get_ok_with_init() {
+        let (_, db, uuid) = test_setup().await;
+        let result = ModelCurve::get(&db).await;
+
+        assert!(result.is_some());
+        let result = result.unwrap();
+        assert_eq!(result.curve_id, 1);
+        assert_eq!(result.curve_name, BrightnessCurve::Linear);
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_curve_update_ok() {
+        let (_, db, uuid) = test_setup().await;
+
+        let result = ModelCurve::update(&db, BrightnessCurve::SCurve).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.curve_id, 1);
+        assert_eq!(result.curve_name, BrightnessCurve::SCurve);
+
+        let result = ModelCurve::get(&db).await.unwrap();
+        assert_eq!(result.curve_name, BrightnessCurve::SCurve);
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_curve_init_keeps_existing() {
+        let (app_envs, db, uuid) = test_setup().await;
+        ModelCurve::update(&db, BrightnessCurve::Gamma)
+            .await
+            .unwrap();
+        db.close().await;
+
+        let db = crate::db::init_db(&app_envs).await.unwrap();
+        let result = ModelCurve::get(&db).await.unwrap();
+
+        assert_eq!(result.curve_name, BrightnessCurve::Gamma);
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
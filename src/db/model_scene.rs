@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+use std::fmt;
+
+use super::{from_json, to_json};
+use crate::{
+    app_error::AppError,
+    light::{LightDuration, LightSettings, Rgb},
+};
+
+/// A named light preset, colour and pixels are stored as json text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModelScene {
+    pub scene_id: i64,
+    pub name: String,
+    pub colour: Option<Rgb>,
+    /// Brightness percentage
+    pub brightness: Option<u8>,
+    pub pixels: Option<Vec<Rgb>>,
+    pub duration: LightDuration,
+}
+
+impl<'r> sqlx::FromRow<'r, SqliteRow> for ModelScene {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            scene_id: row.try_get("scene_id")?,
+            name: row.try_get("name")?,
+            colour: from_json(row, "colour")?,
+            brightness: row.try_get("brightness")?,
+            pixels: from_json(row, "pixels")?,
+            duration: LightDuration::from_minutes(row.try_get("minutes")?),
+        })
+    }
+}
+
+impl fmt::Display for ModelScene {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "scene_id: {}, name:{}, colour:{:?}, brightness:{:?}, pixels:{:?}, duration:{:?}",
+            self.scene_id, self.name, self.colour, self.brightness, self.pixels, self.duration
+        )
+    }
+}
+
+impl ModelScene {
+    /// The settings used to turn the light on
+    pub fn settings(&self) -> LightSettings {
+        LightSettings {
+            brightness: self.brightness,
+            colour: self.colour,
+            pixels: self.pixels.clone(),
+            duration: Some(self.duration),
+        }
+    }
+
+    pub async fn get(db: &SqlitePool, id: i64) -> Result<Option<Self>, AppError> {
+        let sql = "SELECT * FROM scene WHERE scene_id = $1";
+        let result = sqlx::query_as::<_, Self>(sql)
+            .bind(id)
+            .fetch_optional(db)
+            .await?;
+        Ok(result)
+    }
+
+    pub async fn get_all(db: &SqlitePool) -> Result<Vec<Self>, AppError> {
+        let sql = "SELECT * FROM scene ORDER BY name";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_all(db).await?;
+        Ok(result)
+    }
+
+    /// Add a scene, `None` duration uses the default
+    pub async fn add(
+        db: &SqlitePool,
+        name: &str,
+        settings: &LightSettings,
+    ) -> Result<Self, AppError> {
+        let sql = "INSERT INTO scene(name, colour, brightness, pixels, minutes) VALUES ($1, $2, $3, $4, $5) RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(name)
+            .bind(to_json(settings.colour.as_ref()))
+            .bind(settings.brightness)
+            .bind(to_json(settings.pixels.as_ref()))
+            .bind(settings.duration.unwrap_or_default().as_minutes())
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+
+    pub async fn delete(db: &SqlitePool, id: i64) -> Result<(), AppError> {
+        let sql = "DELETE FROM scene WHERE scene_id = $1";
+        sqlx::query(sql).bind(id).execute(db).await?;
+        Ok(())
+    }
+}
+
+/// ModelScene tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_scene -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::tests::{test_cleanup, test_setup};
+
+    use super::*;
+
+    fn gen_settings() -> LightSettings {
+        LightSettings {
+            brightness: Some(60),
+            colour: Some((255, 180, 90)),
+            pixels: Some(vec![(255, 0, 0), (0, 0, 255)]),
+            duration: Some(LightDuration::Never),
+        }
+    }
+
+    #[tokio::test]
+    async fn model_scene_add_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+
+        let result = ModelScene::add(&db, "reading", &gen_settings()).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.scene_id, 1);
+        assert_eq!(result.name, "reading");
+        assert_eq!(result.settings(), gen_settings());
+
+        let result = ModelScene::add(&db, "relax", &LightSettings::default())
+            .await
+            .unwrap();
+        assert!(result.colour.is_none());
+        assert!(result.brightness.is_none());
+        assert!(result.pixels.is_none());
+        assert_eq!(result.duration, LightDuration::default());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_scene_add_err() {
+        let (_app_env, db, uuid) = test_setup().await;
+        ModelScene::add(&db, "reading", &gen_settings())
+            .await
+            .unwrap();
+
+        // Duplicate name
+        let result = ModelScene::add(&db, "reading", &gen_settings()).await;
+        assert!(result.is_err());
+
+        // Brightness out of range
+        let settings = LightSettings {
+            brightness: Some(101),
+            ..LightSettings::default()
+        };
+        let result = ModelScene::add(&db, "cleaning", &settings).await;
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_scene_get_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        ModelScene::add(&db, "reading", &gen_settings())
+            .await
+            .unwrap();
+
+        let result = ModelScene::get(&db, 1).await.unwrap();
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().settings(), gen_settings());
+
+        let result = ModelScene::get(&db, 2).await.unwrap();
+        assert!(result.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_scene_get_all_delete_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        for name in ["relax", "cleaning", "reading"] {
+            ModelScene::add(&db, name, &gen_settings()).await.unwrap();
+        }
+
+        let result = ModelScene::get_all(&db).await.unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].name, "cleaning");
+
+        ModelScene::delete(&db, 1).await.unwrap();
+        let result = ModelScene::get_all(&db).await.unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|scene| scene.name != "relax"));
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fmt;
+
+use crate::{app_error::AppError, light::LightDuration};
+
+/// Default minutes the night light stays on for
+const NIGHT_MINUTES: u16 = 15;
+
+#[derive(sqlx::FromRow, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ModelSetting {
+    pub setting_id: i64,
+    /// Default minutes a manually turned on light stays on for, `NULL` to stay on indefinitely
+    pub light_minutes: Option<u16>,
+    /// Hour from which a manual "on" becomes a night light, `NULL` to disable
+    pub night_start: Option<u8>,
+    /// Hour at which a manual "on" goes back to normal, `NULL` to disable
+    pub night_end: Option<u8>,
+    /// Minutes the night light stays on for, `NULL` to stay on indefinitely
+    pub night_minutes: Option<u16>,
+}
+
+/// Night light settings, sent to the client
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NightLightSetting {
+    pub start: Option<u8>,
+    pub end: Option<u8>,
+    pub duration: LightDuration,
+}
+
+impl fmt::Display for ModelSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "setting_id: {}, light_minutes: {:?}, night_start: {:?}, night_end: {:?}, night_minutes: {:?}",
+            self.setting_id,
+            self.light_minutes,
+            self.night_start,
+            self.night_end,
+            self.night_minutes,
+        )
+    }
+}
+
+impl Default for ModelSetting {
+    fn default() -> Self {
+        Self {
+            setting_id: 1,
+            light_minutes: LightDuration::default().as_minutes(),
+            night_start: None,
+            night_end: None,
+            night_minutes: Some(NIGHT_MINUTES),
+        }
+    }
+}
+
+impl ModelSetting {
+    /// Default duration of a manually turned on light
+    pub const fn light_duration(&self) -> LightDuration {
+        LightDuration::from_minutes(self.light_minutes)
+    }
+
+    pub const fn night_light(&self) -> NightLightSetting {
+        NightLightSetting {
+            start: self.night_start,
+            end: self.night_end,
+            duration: LightDuration::from_minutes(self.night_minutes),
+        }
+    }
+
+    /// Check if a given hour is within the night light window, the window can cross midnight.
+    /// The start hour is included, the end hour is not
+    pub fn is_night(&self, hour: i8) -> bool {
+        let (Some(start), Some(end)) = (self.night_start, self.night_end) else {
+            return false;
+        };
+        let Ok(hour) = u8::try_from(hour) else {
+            return false;
+        };
+        if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        }
+    }
+
+    pub async fn get(db: &SqlitePool) -> Option<Self> {
+        let sql = "SELECT * FROM setting";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_one(db).await;
+        result.ok()
+    }
+
+    pub async fn update_light_duration(
+        db: &SqlitePool,
+        duration: LightDuration,
+    ) -> Result<Self, AppError> {
+        let sql = "UPDATE setting SET light_minutes = $1 RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(duration.as_minutes())
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+
+    pub async fn update_night_light(
+        db: &SqlitePool,
+        night_light: &NightLightSetting,
+    ) -> Result<Self, AppError> {
+        let sql =
+            "UPDATE setting SET night_start = $1, night_end = $2, night_minutes = $3 RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(night_light.start)
+            .bind(night_light.end)
+            .bind(night_light.duration.as_minutes())
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+}
+
+/// ModelSetting tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_setting -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::tests::{test_cleanup, test_setup};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn model_setting_get_ok_with_init() {
+        let (_, db, uuid) = test_setup().await;
+        let result = ModelSetting::get(&db).await;
+
+        assert!(result.is_some());
+        let result = result.unwrap();
+        assert_eq!(result, ModelSetting::default());
+        assert_eq!(result.light_duration(), LightDuration::Minutes(10));
+        assert_eq!(
+            result.night_light(),
+            NightLightSetting {
+                start: None,
+                end: None,
+                duration: LightDuration::Minutes(15)
+            }
+        );
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_setting_update_light_duration_ok() {
+        let (_, db, uuid) = test_setup().await;
+
+        let result = ModelSetting::update_light_duration(&db, LightDuration::Minutes(45)).await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().light_minutes, Some(45));
+
+        let result = ModelSetting::update_light_duration(&db, LightDuration::Never).await;
+        assert!(result.is_ok());
+        assert!(result.unwrap().light_minutes.is_none());
+
+        let result = ModelSetting::get(&db).await.unwrap();
+        assert_eq!(result.light_duration(), LightDuration::Never);
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_setting_update_light_duration_err() {
+        let (_, db, uuid) = test_setup().await;
+
+        let result = ModelSetting::update_light_duration(&db, LightDuration::Minutes(0)).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_setting_update_night_light_ok() {
+        let (_, db, uuid) = test_setup().await;
+        let night_light = NightLightSetting {
+            start: Some(22),
+            end: Some(6),
+            duration: LightDuration::Minutes(5),
+        };
+
+        let result = ModelSetting::update_night_light(&db, &night_light).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.night_light(), night_light);
+        assert_eq!(result.light_minutes, Some(10));
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_setting_update_night_light_err() {
+        let (_, db, uuid) = test_setup().await;
+        let night_light = NightLightSetting {
+            start: Some(24),
+            end: Some(6),
+            duration: LightDuration::Never,
+        };
+
+        let result = ModelSetting::update_night_light(&db, &night_light).await;
+
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[test]
+    fn model_setting_is_night() {
+        let mut setting = ModelSetting::default();
+        assert!(!setting.is_night(23));
+
+        // Crossing midnight
+        setting.night_start = Some(22);
+        setting.night_end = Some(6);
+        for hour in [22, 23, 0, 3, 5] {
+            assert!(setting.is_night(hour));
+        }
+        for hour in [6, 12, 21] {
+            assert!(!setting.is_night(hour));
+        }
+
+        // Same day
+        setting.night_start = Some(1);
+        setting.night_end = Some(4);
+        assert!(setting.is_night(1));
+        assert!(setting.is_night(3));
+        assert!(!setting.is_night(4));
+        assert!(!setting.is_night(23));
+
+        // Only one end set
+        setting.night_end = None;
+        assert!(!setting.is_night(2));
+    }
+}
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+use std::fmt;
+
+use super::{from_json, to_json};
+use crate::{app_error::AppError, light::Rgb};
+
+/// A recurring sunset, the colour is stored as json text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModelSunset {
+    pub sunset_id: i64,
+    pub day: i8,
+    pub hour: i8,
+    pub minute: i8,
+    /// Starting brightness percentage
+    pub brightness: Option<u8>,
+    /// Starting colour, `None` uses the default warm white
+    pub colour: Option<Rgb>,
+    /// Minutes taken to fade to off
+    pub minutes: Option<u16>,
+}
+
+impl<'r> sqlx::FromRow<'r, SqliteRow> for ModelSunset {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            sunset_id: row.try_get("sunset_id")?,
+            day: row.try_get("day")?,
+            hour: row.try_get("hour")?,
+            minute: row.try_get("minute")?,
+            brightness: row.try_get("brightness")?,
+            colour: from_json(row, "colour")?,
+            minutes: row.try_get("minutes")?,
+        })
+    }
+}
+
+impl fmt::Display for ModelSunset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "sunset_id: {}, day:{}, hour:{}, minute:{}, brightness:{:?}, colour:{:?}, minutes:{:?}",
+            self.sunset_id,
+            self.day,
+            self.hour,
+            self.minute,
+            self.brightness,
+            self.colour,
+            self.minutes
+        )
+    }
+}
+
+impl ModelSunset {
+    pub async fn get_all(db: &SqlitePool) -> Result<Vec<Self>, AppError> {
+        let sql = "SELECT * FROM sunset";
+        let result = sqlx::query_as::<_, Self>(sql).fetch_all(db).await?;
+        Ok(result)
+    }
+
+    pub async fn add(
+        db: &SqlitePool,
+        data: (u8, u8, u8),
+        brightness: Option<u8>,
+        colour: Option<Rgb>,
+        minutes: Option<u16>,
+    ) -> Result<Self, AppError> {
+        let sql = "INSERT INTO sunset(day, hour, minute, brightness, colour, minutes) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *";
+        let query = sqlx::query_as::<_, Self>(sql)
+            .bind(data.0)
+            .bind(data.1)
+            .bind(data.2)
+            .bind(brightness)
+            .bind(to_json(colour.as_ref()))
+            .bind(minutes)
+            .fetch_one(db)
+            .await?;
+        Ok(query)
+    }
+
+    pub async fn delete(db: &SqlitePool, id: i64) -> Result<(), AppError> {
+        let sql = "DELETE FROM sunset WHERE sunset_id = $1";
+        sqlx::query(sql).bind(id).execute(db).await?;
+        Ok(())
+    }
+}
+
+/// ModelSunset tests
+///
+/// cargo watch -q -c -w src/ -x 'test model_sunset -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use crate::tests::{test_cleanup, test_setup};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn model_sunset_add_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+
+        let result =
+            ModelSunset::add(&db, (4, 22, 15), Some(60), Some((255, 120, 40)), Some(20)).await;
+
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.sunset_id, 1);
+        assert_eq!(result.day, 4);
+        assert_eq!(result.hour, 22);
+        assert_eq!(result.minute, 15);
+        assert_eq!(result.brightness, Some(60));
+        assert_eq!(result.colour, Some((255, 120, 40)));
+        assert_eq!(result.minutes, Some(20));
+
+        let result = ModelSunset::add(&db, (5, 22, 15), None, None, None)
+            .await
+            .unwrap();
+        assert!(result.brightness.is_none());
+        assert!(result.colour.is_none());
+        assert!(result.minutes.is_none());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_sunset_add_err() {
+        let (_app_env, db, uuid) = test_setup().await;
+
+        let result = ModelSunset::add(&db, (7, 22, 15), None, None, None).await;
+        assert!(result.is_err());
+
+        let result = ModelSunset::add(&db, (1, 22, 15), Some(101), None, None).await;
+        assert!(result.is_err());
+
+        let result = ModelSunset::add(&db, (1, 22, 15), None, None, Some(0)).await;
+        assert!(result.is_err());
+
+        ModelSunset::add(&db, (1, 22, 15), None, None, None)
+            .await
+            .unwrap();
+        let result = ModelSunset::add(&db, (1, 22, 15), None, None, None).await;
+        assert!(result.is_err());
+        test_cleanup(uuid, Some(db)).await;
+    }
+
+    #[tokio::test]
+    async fn model_sunset_get_all_delete_ok() {
+        let (_app_env, db, uuid) = test_setup().await;
+        for i in 0..4 {
+            ModelSunset::add(&db, (i, 21, i), None, None, None)
+                .await
+                .unwrap();
+        }
+
+        let result = ModelSunset::get_all(&db).await.unwrap();
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[2].minute, 2);
+
+        ModelSunset::delete(&db, 1).await.unwrap();
+        let result = ModelSunset::get_all(&db).await.unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].sunset_id, 2);
+        test_cleanup(uuid, Some(db)).await;
+    }
+}
@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Rgb,
+    effect::{Effect, EffectFrame},
+    gradient::lerp,
+};
+
+/// How the transition from one keyframe to the next progresses
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Hold this keyframe until the next one is reached
+    Step,
+}
+
+impl Easing {
+    /// Convert a transition progress, `0.0` to `1.0`, into an eased progress, `0.0` to `1.0`
+    pub fn apply(self, amount: f32) -> f32 {
+        let amount = amount.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => amount,
+            Self::EaseIn => amount * amount,
+            Self::EaseOut => amount * (2.0 - amount),
+            Self::EaseInOut => amount * amount * 2.0f32.mul_add(-amount, 3.0),
+            Self::Step => 0.0,
+        }
+    }
+}
+
+/// A single point of an animation, pixels not included are turned off
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Keyframe {
+    /// Milliseconds since the start of the animation
+    pub offset_ms: u32,
+    pub pixels: Vec<Rgb>,
+    /// Brightness percentage
+    pub brightness: u8,
+    /// Easing used for the transition to the next keyframe
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+/// A user defined sequence of keyframes, keyframes must be ordered by offset, and the first must have an offset of zero
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct Animation {
+    keyframes: Vec<Keyframe>,
+}
+
+impl Animation {
+    pub const fn new(keyframes: Vec<Keyframe>) -> Self {
+        Self { keyframes }
+    }
+
+    /// Time from the first, to the last, keyframe
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(
+            self.keyframes
+                .last()
+                .map_or(0, |keyframe| u64::from(keyframe.offset_ms)),
+        )
+    }
+
+    /// The frame at a given time since the start, times after the last keyframe show the last keyframe
+    pub fn frame_at(&self, elapsed: Duration, num_pixels: usize) -> EffectFrame {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return EffectFrame {
+                brightness: 0.0,
+                pixels: vec![(0, 0, 0); num_pixels],
+            };
+        };
+        let elapsed_ms = u32::try_from(elapsed.as_millis()).unwrap_or(u32::MAX);
+        let (from, to, amount) = match self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.offset_ms > elapsed_ms)
+        {
+            Some(0) => (first, first, 0.0),
+            Some(index) => {
+                let (from, to) = (&self.keyframes[index - 1], &self.keyframes[index]);
+                #[expect(clippy::cast_precision_loss)]
+                let amount = (elapsed_ms - from.offset_ms) as f32
+                    / (to.offset_ms - from.offset_ms).max(1) as f32;
+                (from, to, from.easing.apply(amount))
+            }
+            None => (last, last, 0.0),
+        };
+        let pixel = |keyframe: &Keyframe, index: usize| {
+            keyframe.pixels.get(index).copied().unwrap_or_default()
+        };
+        let pixels = (0..num_pixels)
+            .map(|index| {
+                let (a, b) = (pixel(from, index), pixel(to, index));
+                (
+                    lerp(a.0, b.0, amount),
+                    lerp(a.1, b.1, amount),
+                    lerp(a.2, b.2, amount),
+                )
+            })
+            .collect();
+        EffectFrame {
+            brightness: f32::from(lerp(from.brightness, to.brightness, amount).min(100)) / 100.0,
+            pixels,
+        }
+    }
+
+    /// The frame at a given progress, `0.0` to `1.0`, the animation is stretched to fit, used when the animation is a sunrise profile
+    pub fn frame_at_progress(&self, progress: f32, num_pixels: usize) -> EffectFrame {
+        self.frame_at(
+            self.duration().mul_f32(progress.clamp(0.0, 1.0)),
+            num_pixels,
+        )
+    }
+}
+
+/// An animation played as an effect, either once, or on repeat
+#[derive(Debug)]
+pub struct Playback {
+    pub animation: Animation,
+    pub looping: bool,
+    pub num_pixels: usize,
+}
+
+impl Effect for Playback {
+    fn frame(&self, elapsed: Duration) -> EffectFrame {
+        let elapsed = if self.looping {
+            let duration = self.animation.duration().as_millis().max(1);
+            Duration::from_millis(u64::try_from(elapsed.as_millis() % duration).unwrap_or_default())
+        } else {
+            elapsed
+        };
+        self.animation.frame_at(elapsed, self.num_pixels)
+    }
+}
+
+/// Animation tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_animation -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn gen_animation(easing: Easing) -> Animation {
+        Animation::new(vec![
+            Keyframe {
+                offset_ms: 0,
+                pixels: vec![(0, 0, 0), (200, 0, 0)],
+                brightness: 0,
+                easing,
+            },
+            Keyframe {
+                offset_ms: 1000,
+                pixels: vec![(200, 100, 0), (0, 0, 200)],
+                brightness: 100,
+                easing,
+            },
+            // Missing pixels are off
+            Keyframe {
+                offset_ms: 2000,
+                pixels: vec![],
+                brightness: 50,
+                easing,
+            },
+        ])
+    }
+
+    const fn ms(x: u64) -> Duration {
+        Duration::from_millis(x)
+    }
+
+    #[test]
+    fn light_animation_duration() {
+        assert_eq!(gen_animation(Easing::Linear).duration(), ms(2000));
+        assert_eq!(Animation::new(vec![]).duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn light_animation_keyframes() {
+        let animation = gen_animation(Easing::Linear);
+        let frame = animation.frame_at(ms(0), 3);
+        assert_eq!(frame.pixels, vec![(0, 0, 0), (200, 0, 0), (0, 0, 0)]);
+        assert!(frame.brightness.abs() < f32::EPSILON);
+
+        let frame = animation.frame_at(ms(1000), 3);
+        assert_eq!(frame.pixels, vec![(200, 100, 0), (0, 0, 200), (0, 0, 0)]);
+        assert!((frame.brightness - 1.0).abs() < f32::EPSILON);
+
+        // After the last keyframe
+        let frame = animation.frame_at(ms(5000), 2);
+        assert_eq!(frame.pixels, vec![(0, 0, 0), (0, 0, 0)]);
+        assert!((frame.brightness - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn light_animation_interpolate() {
+        let animation = gen_animation(Easing::Linear);
+        let frame = animation.frame_at(ms(500), 2);
+        assert_eq!(frame.pixels, vec![(100, 50, 0), (100, 0, 100)]);
+        assert!((frame.brightness - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn light_animation_easing() {
+        let frame = gen_animation(Easing::Step).frame_at(ms(999), 2);
+        assert_eq!(frame.pixels, vec![(0, 0, 0), (200, 0, 0)]);
+
+        let frame = gen_animation(Easing::EaseIn).frame_at(ms(500), 2);
+        assert_eq!(frame.pixels[0], (50, 25, 0));
+
+        let frame = gen_animation(Easing::EaseOut).frame_at(ms(500), 2);
+        assert_eq!(frame.pixels[0], (150, 75, 0));
+
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert!(easing.apply(0.0).abs() < f32::EPSILON);
+            assert!((easing.apply(1.0) - 1.0).abs() < f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn light_animation_progress() {
+        let animation = gen_animation(Easing::Linear);
+        assert_eq!(
+            animation.frame_at_progress(0.25, 2),
+            animation.frame_at(ms(500), 2)
+        );
+        assert_eq!(
+            animation.frame_at_progress(2.0, 2),
+            animation.frame_at(ms(2000), 2)
+        );
+    }
+
+    #[test]
+    fn light_animation_playback() {
+        let mut playback = Playback {
+            animation: gen_animation(Easing::Linear),
+            looping: true,
+            num_pixels: 2,
+        };
+        assert_eq!(playback.frame(ms(2500)), playback.frame(ms(500)));
+        assert_eq!(playback.frame(ms(4000)), playback.frame(ms(0)));
+
+        playback.looping = false;
+        assert_eq!(playback.frame(ms(2500)), playback.frame(ms(2000)));
+        assert_ne!(playback.frame(ms(2500)), playback.frame(ms(500)));
+    }
+
+    #[test]
+    fn light_animation_empty() {
+        let frame = Animation::new(vec![]).frame_at(ms(100), 2);
+        assert_eq!(frame.pixels, vec![(0, 0, 0), (0, 0, 0)]);
+        assert!(frame.brightness.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn light_animation_deserialize() {
+        let animation = serde_json::from_str::<Animation>(
+            r#"[{"offset_ms":0,"pixels":[[1,2,3]],"brightness":10},{"offset_ms":500,"pixels":[],"brightness":20,"easing":"ease_in_out"}]"#,
+        );
+        assert!(animation.is_ok());
+        let animation = animation.unwrap();
+        assert_eq!(animation.duration(), ms(500));
+        assert_eq!(animation.keyframes[0].easing, Easing::Linear);
+        assert_eq!(animation.keyframes[1].easing, Easing::EaseInOut);
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+/// Steepness of the exponential curve, higher values keep the light dimmer for longer
+const EXPONENTIAL_RATE: f32 = 5.0;
+
+/// Gamma value commonly used to approximate perceived brightness
+const GAMMA: f32 = 2.2;
+
+/// How the alarm sequence maps progress onto brightness
+#[derive(
+    sqlx::Type,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum BrightnessCurve {
+    #[default]
+    Linear,
+    Exponential,
+    Gamma,
+    SCurve,
+}
+
+impl BrightnessCurve {
+    /// Convert a progress value, `0.0` to `1.0`, into a brightness value, `0.0` to `1.0`
+    pub fn apply(self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => progress,
+            Self::Exponential => (EXPONENTIAL_RATE * progress).exp_m1() / EXPONENTIAL_RATE.exp_m1(),
+            Self::Gamma => progress.powf(GAMMA),
+            Self::SCurve => progress * progress * 2.0f32.mul_add(-progress, 3.0),
+        }
+    }
+}
+
+/// BrightnessCurve tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_curve -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [BrightnessCurve; 4] = [
+        BrightnessCurve::Linear,
+        BrightnessCurve::Exponential,
+        BrightnessCurve::Gamma,
+        BrightnessCurve::SCurve,
+    ];
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.001
+    }
+
+    #[test]
+    fn light_curve_end_points() {
+        for curve in ALL {
+            assert!(approx(curve.apply(0.0), 0.0));
+            assert!(approx(curve.apply(1.0), 1.0));
+        }
+    }
+
+    #[test]
+    fn light_curve_clamped() {
+        for curve in ALL {
+            assert!(approx(curve.apply(-1.0), 0.0));
+            assert!(approx(curve.apply(2.0), 1.0));
+        }
+    }
+
+    #[test]
+    fn light_curve_monotonic() {
+        for curve in ALL {
+            let mut previous = 0.0;
+            for i in 1..=100u8 {
+                let value = curve.apply(f32::from(i) / 100.0);
+                assert!(value >= previous);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn light_curve_values() {
+        assert!(approx(BrightnessCurve::Linear.apply(0.5), 0.5));
+        assert!(approx(BrightnessCurve::SCurve.apply(0.5), 0.5));
+        assert!(approx(BrightnessCurve::Gamma.apply(0.5), 0.2176));
+        assert!(approx(BrightnessCurve::Exponential.apply(0.5), 0.0759));
+        assert!(BrightnessCurve::SCurve.apply(0.1) < BrightnessCurve::Linear.apply(0.1));
+        assert!(BrightnessCurve::Gamma.apply(0.1) < BrightnessCurve::Linear.apply(0.1));
+    }
+
+    #[test]
+    fn light_curve_serialize() {
+        assert_eq!(
+            serde_json::to_string(&BrightnessCurve::SCurve).unwrap_or_default(),
+            r#""s_curve""#
+        );
+        assert_eq!(
+            serde_json::from_str::<BrightnessCurve>(r#""gamma""#).ok(),
+            Some(BrightnessCurve::Gamma)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+const NEVER: &str = "never";
+
+/// How long the light stays on when turned on manually
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LightDuration {
+    Minutes(u16),
+    Never,
+}
+
+impl Default for LightDuration {
+    fn default() -> Self {
+        Self::Minutes(10)
+    }
+}
+
+impl LightDuration {
+    /// Convert from the settings table, where `NULL` is never
+    pub const fn from_minutes(minutes: Option<u16>) -> Self {
+        match minutes {
+            Some(minutes) => Self::Minutes(minutes),
+            None => Self::Never,
+        }
+    }
+
+    /// Convert to the settings table, where `NULL` is never
+    pub const fn as_minutes(self) -> Option<u16> {
+        match self {
+            Self::Minutes(minutes) => Some(minutes),
+            Self::Never => None,
+        }
+    }
+
+    /// `None` if the light should stay on indefinitely
+    pub fn as_duration(self) -> Option<Duration> {
+        self.as_minutes()
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+}
+
+/// Serialized as either a number of minutes, or the string "never"
+impl Serialize for LightDuration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Minutes(minutes) => serializer.serialize_u16(*minutes),
+            Self::Never => serializer.serialize_str(NEVER),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LightDuration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Minutes(u16),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Minutes(minutes) => Ok(Self::Minutes(minutes)),
+            Raw::Text(text) if text == NEVER => Ok(Self::Never),
+            Raw::Text(text) => Err(de::Error::custom(format!("{text} not a valid duration"))),
+        }
+    }
+}
+
+/// LightDuration tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_duration -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_duration_serialize() {
+        assert_eq!(
+            serde_json::to_string(&LightDuration::Minutes(30)).unwrap(),
+            "30"
+        );
+        assert_eq!(
+            serde_json::to_string(&LightDuration::Never).unwrap(),
+            r#""never""#
+        );
+    }
+
+    #[test]
+    fn light_duration_deserialize() {
+        assert_eq!(
+            serde_json::from_str::<LightDuration>("30").unwrap(),
+            LightDuration::Minutes(30)
+        );
+        assert_eq!(
+            serde_json::from_str::<LightDuration>(r#""never""#).unwrap(),
+            LightDuration::Never
+        );
+        assert!(serde_json::from_str::<LightDuration>(r#""always""#).is_err());
+        assert!(serde_json::from_str::<LightDuration>("-1").is_err());
+    }
+
+    #[test]
+    fn light_duration_minutes() {
+        assert_eq!(
+            LightDuration::from_minutes(Some(5)),
+            LightDuration::Minutes(5)
+        );
+        assert_eq!(LightDuration::from_minutes(None), LightDuration::Never);
+        assert_eq!(LightDuration::Minutes(5).as_minutes(), Some(5));
+        assert_eq!(LightDuration::Never.as_minutes(), None);
+        assert_eq!(
+            LightDuration::Minutes(2).as_duration(),
+            Some(Duration::from_secs(120))
+        );
+        assert!(LightDuration::Never.as_duration().is_none());
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DEFAULT_COLOUR, Rgb,
+    animation::{Animation, Playback},
+};
+
+/// Lowest brightness of the breathe effect, so the light never fully turns off
+const BREATHE_MIN: f32 = 0.05;
+
+/// Warm flame colour used by the candle effect
+const CANDLE_COLOUR: Rgb = (255, 110, 15);
+
+/// Number of trailing pixels in the chase effect
+const CHASE_TAIL: usize = 3;
+
+/// Decay rate of each pulse, higher values give a sharper beat
+const PULSE_DECAY: f32 = 6.0;
+
+/// A single frame of an effect, every pixel is given a colour, and the whole strip shares a brightness
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectFrame {
+    /// From `0.0` to `1.0`
+    pub brightness: f32,
+    pub pixels: Vec<Rgb>,
+}
+
+/// An animation that produces a frame for any given time since it started.
+/// Frames must only depend on the elapsed time, so that effects are deterministic
+pub trait Effect: Send + Sync + std::fmt::Debug {
+    fn frame(&self, elapsed: Duration) -> EffectFrame;
+}
+
+/// The built in effects
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectName {
+    Breathe,
+    Candle,
+    Chase,
+    Pulse,
+    Rainbow,
+}
+
+/// Requested effect, `None` values use the effect defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffectSettings {
+    pub name: EffectName,
+    pub colour: Option<Rgb>,
+    /// Brightness percentage
+    pub brightness: Option<u8>,
+    /// Length of one cycle of the effect
+    pub period_ms: Option<u32>,
+}
+
+impl EffectName {
+    /// Default length of one cycle of the effect
+    const fn period_ms(self) -> u32 {
+        match self {
+            Self::Breathe => 4000,
+            // How often the candle picks a new flicker level, levels are interpolated in between
+            Self::Candle => 120,
+            Self::Chase => 1200,
+            Self::Pulse => 1000,
+            Self::Rainbow => 10_000,
+        }
+    }
+}
+
+impl EffectSettings {
+    /// Create the effect for a strip with a given number of pixels
+    pub fn build(&self, num_pixels: usize) -> Box<dyn Effect> {
+        let colour = self.colour.unwrap_or(DEFAULT_COLOUR);
+        let period = Duration::from_millis(u64::from(
+            self.period_ms
+                .unwrap_or_else(|| self.name.period_ms())
+                .max(1),
+        ));
+        match self.name {
+            EffectName::Breathe => Box::new(Breathe {
+                colour,
+                num_pixels,
+                period,
+            }),
+            EffectName::Candle => Box::new(Candle {
+                colour: self.colour.unwrap_or(CANDLE_COLOUR),
+                num_pixels,
+                period,
+            }),
+            EffectName::Chase => Box::new(Chase {
+                colour,
+                num_pixels,
+                period,
+            }),
+            EffectName::Pulse => Box::new(Pulse {
+                colour,
+                num_pixels,
+                period,
+            }),
+            EffectName::Rainbow => Box::new(Rainbow { num_pixels, period }),
+        }
+    }
+}
+
+/// An effect that is currently being displayed
+#[derive(Debug)]
+pub struct RunningEffect {
+    brightness: f32,
+    effect: Box<dyn Effect>,
+    started: Instant,
+    until: Option<Duration>,
+}
+
+impl RunningEffect {
+    /// Start an effect now, `None` brightness is full brightness
+    pub fn new(settings: &EffectSettings, num_pixels: usize) -> Self {
+        Self {
+            brightness: settings
+                .brightness
+                .map_or(1.0, |percent| f32::from(percent.min(100)) / 100.0),
+            effect: settings.build(num_pixels),
+            started: Instant::now(),
+            until: None,
+        }
+    }
+
+    /// Start playing an animation now, a one-shot animation finishes after the last keyframe
+    pub fn animation(animation: Animation, looping: bool, num_pixels: usize) -> Self {
+        Self {
+            brightness: 1.0,
+            until: (!looping).then(|| animation.duration()),
+            effect: Box::new(Playback {
+                animation,
+                looping,
+                num_pixels,
+            }),
+            started: Instant::now(),
+        }
+    }
+
+    /// The current frame, scaled by the requested brightness, `None` once a one-shot animation has finished
+    pub fn frame(&self) -> Option<EffectFrame> {
+        let elapsed = self.started.elapsed();
+        if self.until.is_some_and(|until| elapsed > until) {
+            return None;
+        }
+        let mut frame = self.effect.frame(elapsed);
+        frame.brightness *= self.brightness;
+        Some(frame)
+    }
+}
+
+/// Position within the current cycle, from `0.0` to `1.0`
+fn phase(elapsed: Duration, period: Duration) -> f32 {
+    let period = period.as_millis().max(1);
+    #[expect(clippy::cast_precision_loss)]
+    let phase = (elapsed.as_millis() % period) as f32 / period as f32;
+    phase
+}
+
+/// Scale a colour by a level, from `0.0` to `1.0`
+fn scale(colour: Rgb, level: f32) -> Rgb {
+    let level = level.clamp(0.0, 1.0);
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let channel = |value: u8| (f32::from(value) * level).round() as u8;
+    (channel(colour.0), channel(colour.1), channel(colour.2))
+}
+
+/// Convert a hue, from `0.0` to `1.0`, into a fully saturated colour
+fn hue_to_rgb(hue: f32) -> Rgb {
+    let hue = hue.rem_euclid(1.0) * 6.0;
+    let rising = hue.fract();
+    let falling = 1.0 - rising;
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let (red, green, blue) = match hue as u8 {
+        0 => (1.0, rising, 0.0),
+        1 => (falling, 1.0, 0.0),
+        2 => (0.0, 1.0, rising),
+        3 => (0.0, falling, 1.0),
+        4 => (rising, 0.0, 1.0),
+        _ => (1.0, 0.0, falling),
+    };
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let channel = |value: f32| (value * 255.0).round() as u8;
+    (channel(red), channel(green), channel(blue))
+}
+
+/// Deterministic pseudo random value, from `0.0` to `1.0`, using the splitmix64 finaliser
+fn noise(pixel: usize, tick: u64) -> f32 {
+    let mut x = (pixel as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ tick.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    #[expect(clippy::cast_precision_loss)]
+    let value = (x >> 40) as f32 / (1u64 << 24) as f32;
+    value
+}
+
+/// Every pixel slowly fades in and out
+#[derive(Debug)]
+struct Breathe {
+    colour: Rgb,
+    num_pixels: usize,
+    period: Duration,
+}
+
+impl Effect for Breathe {
+    fn frame(&self, elapsed: Duration) -> EffectFrame {
+        let wave = (1.0 - (phase(elapsed, self.period) * TAU).cos()) / 2.0;
+        EffectFrame {
+            brightness: (1.0 - BREATHE_MIN).mul_add(wave, BREATHE_MIN),
+            pixels: vec![self.colour; self.num_pixels],
+        }
+    }
+}
+
+/// Each pixel flickers independently, like a candle flame
+#[derive(Debug)]
+struct Candle {
+    colour: Rgb,
+    num_pixels: usize,
+    period: Duration,
+}
+
+impl Effect for Candle {
+    fn frame(&self, elapsed: Duration) -> EffectFrame {
+        let tick =
+            u64::try_from(elapsed.as_millis() / self.period.as_millis().max(1)).unwrap_or_default();
+        let amount = phase(elapsed, self.period);
+        let pixels = (0..self.num_pixels)
+            .map(|pixel| {
+                let from = noise(pixel, tick);
+                let to = noise(pixel, tick + 1);
+                scale(
+                    self.colour,
+                    0.5f32.mul_add((to - from).mul_add(amount, from), 0.5),
+                )
+            })
+            .collect();
+        EffectFrame {
+            brightness: 1.0,
+            pixels,
+        }
+    }
+}
+
+/// A single lit pixel, with a fading tail, moves along the strip
+#[derive(Debug)]
+struct Chase {
+    colour: Rgb,
+    num_pixels: usize,
+    period: Duration,
+}
+
+impl Effect for Chase {
+    fn frame(&self, elapsed: Duration) -> EffectFrame {
+        #[expect(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let head = (phase(elapsed, self.period) * self.num_pixels as f32) as usize;
+        let pixels = (0..self.num_pixels)
+            .map(|pixel| {
+                let behind = (head + self.num_pixels - pixel) % self.num_pixels.max(1);
+                if behind > CHASE_TAIL {
+                    (0, 0, 0)
+                } else {
+                    #[expect(clippy::cast_precision_loss)]
+                    let level = 1.0 - behind as f32 / (CHASE_TAIL + 1) as f32;
+                    scale(self.colour, level)
+                }
+            })
+            .collect();
+        EffectFrame {
+            brightness: 1.0,
+            pixels,
+        }
+    }
+}
+
+/// Every pixel flashes on, and quickly decays, once a cycle, like a heartbeat
+#[derive(Debug)]
+struct Pulse {
+    colour: Rgb,
+    num_pixels: usize,
+    period: Duration,
+}
+
+impl Effect for Pulse {
+    fn frame(&self, elapsed: Duration) -> EffectFrame {
+        EffectFrame {
+            brightness: (-PULSE_DECAY * phase(elapsed, self.period)).exp(),
+            pixels: vec![self.colour; self.num_pixels],
+        }
+    }
+}
+
+/// The full colour wheel spread across the strip, rotating once a cycle
+#[derive(Debug)]
+struct Rainbow {
+    num_pixels: usize,
+    period: Duration,
+}
+
+impl Effect for Rainbow {
+    fn frame(&self, elapsed: Duration) -> EffectFrame {
+        let offset = phase(elapsed, self.period);
+        let pixels = (0..self.num_pixels)
+            .map(|pixel| {
+                #[expect(clippy::cast_precision_loss)]
+                let hue = offset + pixel as f32 / self.num_pixels as f32;
+                hue_to_rgb(hue)
+            })
+            .collect();
+        EffectFrame {
+            brightness: 1.0,
+            pixels,
+        }
+    }
+}
+
+/// Effect tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_effect -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [EffectName; 5] = [
+        EffectName::Breathe,
+        EffectName::Candle,
+        EffectName::Chase,
+        EffectName::Pulse,
+        EffectName::Rainbow,
+    ];
+
+    fn settings(name: EffectName) -> EffectSettings {
+        EffectSettings {
+            name,
+            colour: Some((255, 0, 0)),
+            brightness: None,
+            period_ms: Some(1000),
+        }
+    }
+
+    const fn ms(x: u64) -> Duration {
+        Duration::from_millis(x)
+    }
+
+    #[test]
+    fn light_effect_deterministic() {
+        for name in ALL {
+            let first = settings(name).build(8);
+            let second = settings(name).build(8);
+            for elapsed in [0, 50, 333, 999, 12_345] {
+                assert_eq!(first.frame(ms(elapsed)), second.frame(ms(elapsed)));
+                assert_eq!(first.frame(ms(elapsed)), first.frame(ms(elapsed)));
+            }
+        }
+    }
+
+    #[test]
+    fn light_effect_pixel_count() {
+        for name in ALL {
+            for num_pixels in [1, 8, 30] {
+                let effect = settings(name).build(num_pixels);
+                let frame = effect.frame(ms(250));
+                assert_eq!(frame.pixels.len(), num_pixels);
+                assert!((0.0..=1.0).contains(&frame.brightness));
+            }
+        }
+    }
+
+    #[test]
+    fn light_effect_breathe() {
+        let effect = settings(EffectName::Breathe).build(8);
+        assert!((effect.frame(ms(0)).brightness - BREATHE_MIN).abs() < 0.001);
+        assert!((effect.frame(ms(500)).brightness - 1.0).abs() < 0.001);
+        assert!((effect.frame(ms(1000)).brightness - BREATHE_MIN).abs() < 0.001);
+        assert!(effect.frame(ms(250)).brightness < effect.frame(ms(400)).brightness);
+        assert_eq!(effect.frame(ms(250)).pixels, vec![(255, 0, 0); 8]);
+    }
+
+    #[test]
+    fn light_effect_pulse() {
+        let effect = settings(EffectName::Pulse).build(8);
+        assert!((effect.frame(ms(0)).brightness - 1.0).abs() < f32::EPSILON);
+        assert!(effect.frame(ms(100)).brightness > effect.frame(ms(500)).brightness);
+        assert!(effect.frame(ms(999)).brightness < 0.01);
+        assert_eq!(effect.frame(ms(1000)), effect.frame(ms(0)));
+    }
+
+    #[test]
+    fn light_effect_chase() {
+        let effect = settings(EffectName::Chase).build(8);
+        let frame = effect.frame(ms(0));
+        assert_eq!(frame.pixels[0], (255, 0, 0));
+        assert_eq!(frame.pixels[1], (0, 0, 0));
+        assert_eq!(frame.pixels[7], (191, 0, 0));
+        assert_eq!(frame.pixels[6], (128, 0, 0));
+        assert_eq!(frame.pixels[5], (64, 0, 0));
+        assert_eq!(frame.pixels[4], (0, 0, 0));
+
+        // Head moves one pixel every eighth of a cycle
+        let frame = effect.frame(ms(125));
+        assert_eq!(frame.pixels[1], (255, 0, 0));
+        assert_eq!(frame.pixels[0], (191, 0, 0));
+    }
+
+    #[test]
+    fn light_effect_rainbow() {
+        let effect = settings(EffectName::Rainbow).build(6);
+        let frame = effect.frame(ms(0));
+        assert_eq!(
+            frame.pixels,
+            vec![
+                (255, 0, 0),
+                (255, 255, 0),
+                (0, 255, 0),
+                (0, 255, 255),
+                (0, 0, 255),
+                (255, 0, 255)
+            ]
+        );
+        // Half way through a cycle, the colour wheel has rotated by half
+        let frame = effect.frame(ms(500));
+        assert_eq!(frame.pixels[0], (0, 255, 255));
+        assert_eq!(frame.pixels[3], (255, 0, 0));
+    }
+
+    #[test]
+    fn light_effect_candle() {
+        let effect = settings(EffectName::Candle).build(8);
+        let frame = effect.frame(ms(0));
+        // Each pixel flickers independently
+        assert!(frame.pixels.windows(2).any(|pair| pair[0] != pair[1]));
+        for pixel in &frame.pixels {
+            assert!(pixel.0 >= 127);
+            assert_eq!(pixel.1, 0);
+        }
+        // Levels change over time, but smoothly
+        assert_ne!(effect.frame(ms(0)), effect.frame(ms(500)));
+        let (a, b) = (effect.frame(ms(60)), effect.frame(ms(61)));
+        for (a, b) in a.pixels.iter().zip(&b.pixels) {
+            assert!(a.0.abs_diff(b.0) <= 3);
+        }
+    }
+
+    #[test]
+    fn light_effect_hue_to_rgb() {
+        assert_eq!(hue_to_rgb(0.0), (255, 0, 0));
+        assert_eq!(hue_to_rgb(1.0 / 3.0), (0, 255, 0));
+        assert_eq!(hue_to_rgb(2.0 / 3.0), (0, 0, 255));
+        assert_eq!(hue_to_rgb(1.0), (255, 0, 0));
+        assert_eq!(hue_to_rgb(-1.0 / 3.0), (0, 0, 255));
+    }
+
+    #[test]
+    fn light_effect_serialize() {
+        assert_eq!(
+            serde_json::from_str::<EffectName>(r#""rainbow""#).ok(),
+            Some(EffectName::Rainbow)
+        );
+        assert!(serde_json::from_str::<EffectName>(r#""strobe""#).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use std::{f32::consts::TAU, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Default maximum minutes an alarm escalates for, before timing out
+pub const ESCALATION_MINUTES: u16 = 15;
+
+/// Time taken for a single pulse, from dim to bright and back again
+const PULSE_PERIOD_MS: u64 = 2000;
+
+/// Time taken for a single flash, on and then off
+const FLASH_PERIOD_MS: u64 = 1000;
+
+/// Lowest brightness reached by a pulse, so that the light never appears to turn off
+const PULSE_MIN: f32 = 0.2;
+
+/// What the alarm does once the hold at full brightness has finished, and it still hasn't been dismissed
+#[derive(
+    sqlx::Type, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum Escalation {
+    /// Smoothly fade between dim and full brightness
+    Pulse,
+    /// Alternate between off and full brightness
+    Flash,
+}
+
+impl Escalation {
+    /// Brightness multiplier, `0.0` to `1.0`, for a given time since the escalation started
+    pub fn level(self, elapsed: Duration) -> f32 {
+        match self {
+            Self::Pulse => {
+                let phase = phase(elapsed, PULSE_PERIOD_MS);
+                let wave = 0.5 - 0.5 * (phase * TAU).cos();
+                (1.0 - PULSE_MIN).mul_add(1.0 - wave, PULSE_MIN)
+            }
+            Self::Flash => {
+                if phase(elapsed, FLASH_PERIOD_MS) < 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Position within a repeating period, from `0.0` to `1.0`
+fn phase(elapsed: Duration, period_ms: u64) -> f32 {
+    #[expect(clippy::cast_precision_loss)]
+    let phase = (elapsed.as_millis() % u128::from(period_ms)) as f32 / period_ms as f32;
+    phase
+}
+
+/// Escalation tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_escalation -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn ms(x: u64) -> Duration {
+        Duration::from_millis(x)
+    }
+
+    #[test]
+    fn light_escalation_pulse() {
+        // Starts at full brightness, following on from the hold
+        assert!((Escalation::Pulse.level(ms(0)) - 1.0).abs() < 0.001);
+        assert!((Escalation::Pulse.level(ms(1000)) - PULSE_MIN).abs() < 0.001);
+        assert!((Escalation::Pulse.level(ms(2000)) - 1.0).abs() < 0.001);
+        for elapsed in (0..4000).step_by(100) {
+            let level = Escalation::Pulse.level(ms(elapsed));
+            assert!((PULSE_MIN - 0.001..=1.0).contains(&level));
+        }
+    }
+
+    #[test]
+    fn light_escalation_flash() {
+        assert!((Escalation::Flash.level(ms(0)) - 1.0).abs() < f32::EPSILON);
+        assert!((Escalation::Flash.level(ms(499)) - 1.0).abs() < f32::EPSILON);
+        assert!(Escalation::Flash.level(ms(500)).abs() < f32::EPSILON);
+        assert!(Escalation::Flash.level(ms(999)).abs() < f32::EPSILON);
+        assert!((Escalation::Flash.level(ms(1000)) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn light_escalation_serialize() {
+        assert_eq!(
+            serde_json::to_string(&Escalation::Pulse).unwrap_or_default(),
+            r#""pulse""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Escalation>(r#""flash""#).ok(),
+            Some(Escalation::Flash)
+        );
+    }
+}
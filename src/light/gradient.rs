@@ -0,0 +1,110 @@
+/// A colour at a given point of the alarm sequence, `progress` is from `0.0` to `1.0`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColourStop {
+    pub progress: f32,
+    pub colour: (u8, u8, u8),
+}
+
+impl ColourStop {
+    const fn new(progress: f32, colour: (u8, u8, u8)) -> Self {
+        Self { progress, colour }
+    }
+}
+
+/// Dawn, from a deep red, through orange, to a cool white
+pub const SUNRISE: [ColourStop; 5] = [
+    ColourStop::new(0.0, (255, 12, 0)),
+    ColourStop::new(0.25, (255, 60, 0)),
+    ColourStop::new(0.5, (255, 130, 10)),
+    ColourStop::new(0.75, (255, 200, 60)),
+    ColourStop::new(1.0, (255, 235, 200)),
+];
+
+/// Linearly interpolate a single channel
+pub fn lerp(from: u8, to: u8, amount: f32) -> u8 {
+    let from = f32::from(from);
+    let to = f32::from(to);
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let output = (to - from).mul_add(amount, from).round().clamp(0.0, 255.0) as u8;
+    output
+}
+
+/// Get the colour at a given progress, interpolating between the two surrounding stops.
+/// Stops must be ordered by progress, values outside the first and last stop are clamped
+pub fn colour_at(stops: &[ColourStop], progress: f32) -> (u8, u8, u8) {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return (0, 0, 0);
+    };
+    if progress <= first.progress {
+        return first.colour;
+    }
+    if progress >= last.progress {
+        return last.colour;
+    }
+    stops
+        .windows(2)
+        .find(|pair| progress <= pair[1].progress)
+        .map_or(last.colour, |pair| {
+            let (from, to) = (pair[0], pair[1]);
+            let span = to.progress - from.progress;
+            let amount = if span > 0.0 {
+                (progress - from.progress) / span
+            } else {
+                1.0
+            };
+            (
+                lerp(from.colour.0, to.colour.0, amount),
+                lerp(from.colour.1, to.colour.1, amount),
+                lerp(from.colour.2, to.colour.2, amount),
+            )
+        })
+}
+
+/// Gradient tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_gradient -- --test-threads=1 --nocapture'
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_gradient_end_points() {
+        assert_eq!(colour_at(&SUNRISE, 0.0), SUNRISE[0].colour);
+        assert_eq!(colour_at(&SUNRISE, 1.0), SUNRISE[4].colour);
+        assert_eq!(colour_at(&SUNRISE, -1.0), SUNRISE[0].colour);
+        assert_eq!(colour_at(&SUNRISE, 2.0), SUNRISE[4].colour);
+    }
+
+    #[test]
+    fn light_gradient_on_stop() {
+        for stop in SUNRISE {
+            assert_eq!(colour_at(&SUNRISE, stop.progress), stop.colour);
+        }
+    }
+
+    #[test]
+    fn light_gradient_interpolate() {
+        let stops = [
+            ColourStop::new(0.0, (0, 100, 200)),
+            ColourStop::new(1.0, (100, 200, 0)),
+        ];
+        assert_eq!(colour_at(&stops, 0.5), (50, 150, 100));
+        assert_eq!(colour_at(&stops, 0.25), (25, 125, 150));
+    }
+
+    #[test]
+    fn light_gradient_gets_whiter() {
+        let mut previous = colour_at(&SUNRISE, 0.0);
+        for i in 1..=10u8 {
+            let colour = colour_at(&SUNRISE, f32::from(i) / 10.0);
+            assert!(colour.1 >= previous.1);
+            assert!(colour.2 >= previous.2);
+            previous = colour;
+        }
+    }
+
+    #[test]
+    fn light_gradient_empty() {
+        assert_eq!(colour_at(&[], 0.5), (0, 0, 0));
+    }
+}
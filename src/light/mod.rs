@@ -0,0 +1,962 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    C,
+    app_env::{AppEnv, LedOutput, LedStrip, LedTransport},
+    blinkt::{self, CalibrationSettings},
+    db::AlarmOutcome,
+    message_handler::Msg,
+    sleep,
+};
+use async_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+mod animation;
+mod curve;
+mod duration;
+mod effect;
+mod escalation;
+mod gradient;
+mod render;
+mod sequence;
+mod sunset;
+mod supervisor;
+
+pub use animation::{Animation, Keyframe};
+pub use curve::BrightnessCurve;
+pub use duration::LightDuration;
+pub use effect::{EffectName, EffectSettings};
+pub use escalation::Escalation;
+pub use sequence::{AlarmSequence, RAMP_MINUTES};
+pub use sunset::SunsetSequence;
+pub use supervisor::LedHealth;
+
+use effect::RunningEffect;
+use render::{RenderStats, Renderer};
+use sequence::Frame;
+use supervisor::Connect;
+
+/// Red, green, and blue values
+pub type Rgb = (u8, u8, u8);
+
+/// Default colours for the LED strip
+const DEFAULT_COLOUR: (u8, u8, u8) = (255, 200, 15);
+
+/// How often the alarm, or sunset, sequence frame is recomputed
+const RENDER_INTERVAL_MS: u64 = 500;
+
+/// How often an effect, or escalating alarm, frame is recomputed
+const EFFECT_INTERVAL_MS: u64 = 40;
+
+/// Default length of a snooze
+const SNOOZE_MINUTES: u16 = 10;
+
+/// How long the light stays on for, when it was turned on to preview a calibration
+const CALIBRATION_PREVIEW_MINUTES: u16 = 5;
+
+/// Dim red, with a touch of amber, for the night light
+const NIGHT_LIGHT_COLOUR: (u8, u8, u8) = (255, 24, 0);
+
+/// Night light brightness, just enough to see by
+const NIGHT_LIGHT_BRIGHTNESS: f32 = 0.03;
+
+/// What is currently driving the light
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case", tag = "name")]
+pub enum LightMode {
+    Alarm {
+        step: u8,
+        /// Pulsing, or flashing, after the hold has finished
+        escalating: bool,
+    },
+    Animation {
+        animation_id: i64,
+    },
+    Effect {
+        effect: EffectName,
+    },
+    Manual,
+    NightLight,
+    #[default]
+    Off,
+    Scene {
+        scene_id: i64,
+    },
+    Sunset,
+}
+
+/// Current state of the light, sent to the client
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LedStatus {
+    pub status: bool,
+    pub mode: LightMode,
+    /// Brightness percentage
+    pub brightness: u8,
+    pub colour: (u8, u8, u8),
+    /// Individual pixel colours, `None` when every pixel is set to `colour`
+    pub pixels: Option<Vec<Rgb>>,
+    /// Seconds until the alarm resumes, `None` when not snoozed
+    pub snooze_remaining: Option<u64>,
+    /// Seconds until a manually turned on light, or a sunset, turns itself off, `None` when it will stay on
+    pub off_remaining: Option<u64>,
+    /// Whether the blinkt is connected and writing successfully
+    pub health: LedHealth,
+    /// Frame timing statistics
+    pub render: RenderStats,
+}
+
+/// Requested settings when turning the light on, `None` values use the defaults
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LightSettings {
+    /// Brightness percentage
+    pub brightness: Option<u8>,
+    pub colour: Option<(u8, u8, u8)>,
+    /// Individual pixel colours, any pixels not included are turned off
+    pub pixels: Option<Vec<Rgb>>,
+    /// How long until the light turns itself off
+    pub duration: Option<LightDuration>,
+}
+
+pub struct LightControl {
+    alarm: Option<AlarmSequence>,
+    brightness: f32,
+    /// The saved calibration, a preview is only applied to the renderer
+    calibration: CalibrationSettings,
+    cancel_token: Option<CancellationToken>,
+    colours: (u8, u8, u8),
+    effect: Option<RunningEffect>,
+    light_tx: Sender<LightMsg>,
+    mode: LightMode,
+    msg_tx: Sender<Msg>,
+    /// Length of the configured strip, effects, animations, and the night light are scaled to fit
+    num_pixels: usize,
+    off_at: Option<Instant>,
+    pixels: Option<Vec<Rgb>>,
+    /// Writes frames to the blinkt on a dedicated thread
+    renderer: Renderer,
+    sunset: Option<SunsetSequence>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LightMsg {
+    Alarm(AlarmSequence),
+    /// Animation id, the animation, and if it should loop
+    Animation(i64, Animation, bool),
+    Calibration(CalibrationSettings),
+    /// Calibration to preview, and the colour to show if the light is off
+    CalibrationPreview(CalibrationSettings, Option<Rgb>),
+    CalibrationPreviewEnd,
+    Dismiss,
+    Effect(EffectSettings),
+    EffectStop,
+    Exit,
+    Get(Sender<LedStatus>),
+    NightLight(LightDuration),
+    Off,
+    On(LightSettings),
+    Render,
+    Scene(i64, LightSettings),
+    Snooze(Option<u16>),
+    SnoozeEnd,
+    Sunset(SunsetSequence),
+}
+
+impl LightControl {
+    fn new(
+        msg_tx: &Sender<Msg>,
+        tx: &Sender<LightMsg>,
+        connect: Connect,
+        num_pixels: usize,
+    ) -> Self {
+        Self {
+            alarm: None,
+            brightness: 0.0,
+            calibration: CalibrationSettings::default(),
+            cancel_token: None,
+            colours: (0, 0, 0),
+            effect: None,
+            light_tx: C!(tx),
+            mode: LightMode::Off,
+            msg_tx: C!(msg_tx),
+            num_pixels,
+            off_at: None,
+            pixels: None,
+            renderer: Renderer::new(connect, msg_tx),
+            sunset: None,
+        }
+    }
+
+    /// Send settings to the blinkt, to actually turn it on or off
+    /// Brightness is spread across both the 5-bit global brightness and the 8-bit colour channels
+    fn display(&mut self) {
+        let pixels = (0..self.num_pixels)
+            .map(|index| {
+                let (red, green, blue) = self.pixels.as_ref().map_or(self.colours, |pixels| {
+                    pixels.get(index).copied().unwrap_or_default()
+                });
+                let mut pixel = blinkt::Pixel::default();
+                pixel.set_rgb_level(red, green, blue, self.brightness);
+                pixel
+            })
+            .collect();
+        self.renderer.show(pixels);
+    }
+
+    /// Apply a calibration to the renderer, used from the next time the blinkt is shown
+    const fn apply_calibration(&mut self, settings: CalibrationSettings) {
+        self.renderer.set_calibration(settings);
+    }
+
+    /// Save a new calibration, and redisplay the current frame with it
+    fn calibrate(&mut self, settings: CalibrationSettings) {
+        self.calibration = settings;
+        self.apply_calibration(settings);
+        self.display();
+    }
+
+    /// End a calibration preview, going back to the saved calibration
+    fn calibration_preview_end(&mut self) {
+        self.apply_calibration(self.calibration);
+        self.display();
+    }
+
+    /// Preview a calibration without saving it, if the light is off it is turned on, in the given colour, so that the preview can be seen
+    /// The preview lasts until it is ended, the light is turned off, or a calibration is saved
+    async fn calibration_preview(&mut self, settings: CalibrationSettings, colour: Option<Rgb>) {
+        self.apply_calibration(settings);
+        if self.mode == LightMode::Off {
+            self.cancel_thead();
+            self.activate(
+                LightMode::Manual,
+                LightDuration::Minutes(CALIBRATION_PREVIEW_MINUTES),
+                1.0,
+                colour.unwrap_or(DEFAULT_COLOUR),
+                None,
+            );
+            self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+        } else {
+            self.display();
+        }
+    }
+
+    /// Turn off the blinkt
+    async fn turn_off(&mut self) {
+        self.alarm = None;
+        self.brightness = 0.0;
+        self.colours = (0, 0, 0);
+        self.mode = LightMode::Off;
+        self.off_at = None;
+        self.pixels = None;
+        self.sunset = None;
+        self.effect = None;
+        self.apply_calibration(self.calibration);
+        self.display();
+        self.cancel_thead();
+        self.msg_tx.send(Msg::StatusFile(None)).await.ok();
+    }
+
+    /// Create and set and cancel token, and copy a sender
+    fn get_token_sender(&mut self) -> (CancellationToken, Sender<LightMsg>) {
+        let token = CancellationToken::new();
+        self.cancel_token = Some(C!(token));
+        (token, C!(self.light_tx))
+    }
+
+    /// Cancel the sleeping thread
+    fn cancel_thead(&self) {
+        if let Some(token) = &self.cancel_token {
+            token.cancel();
+        }
+    }
+
+    /// Set the light status, and spawn a thread to turn it off once the duration has passed
+    fn activate(
+        &mut self,
+        mode: LightMode,
+        duration: LightDuration,
+        brightness: f32,
+        colours: (u8, u8, u8),
+        pixels: Option<Vec<Rgb>>,
+    ) {
+        self.alarm = None;
+        self.brightness = brightness;
+        self.colours = colours;
+        self.mode = mode;
+        self.pixels = pixels;
+        self.sunset = None;
+        self.effect = None;
+        let (token, tx) = self.get_token_sender();
+        self.display();
+        self.off_at = duration.as_duration().map(|limit| {
+            tokio::spawn(async move {
+                token
+                    .run_until_cancelled(async move {
+                        tokio::time::sleep(limit).await;
+                        tx.send(LightMsg::Off).await.ok();
+                    })
+                    .await;
+            });
+            Instant::now() + limit
+        });
+    }
+
+    /// Turn the light on, defaults to full brightness in the default colour, for the default duration
+    fn turn_on(&mut self, settings: LightSettings) {
+        self.apply_settings(LightMode::Manual, settings);
+    }
+
+    /// Set the brightness, colour, pixels, and duration in one go, `None` values use the defaults
+    fn apply_settings(&mut self, mode: LightMode, settings: LightSettings) {
+        let brightness = settings
+            .brightness
+            .map_or(1.0, |percent| f32::from(percent.min(100)) / 100.0);
+        self.activate(
+            mode,
+            settings.duration.unwrap_or_default(),
+            brightness,
+            settings.colour.unwrap_or(DEFAULT_COLOUR),
+            settings.pixels,
+        );
+    }
+
+    /// Apply a stored scene, replacing whatever the light is currently doing, including an in progress alarm
+    async fn scene(&mut self, scene_id: i64, settings: LightSettings) {
+        self.cancel_thead();
+        self.apply_settings(LightMode::Scene { scene_id }, settings);
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// The night light pixels, only the middle two pixels are lit, or the middle pixel of a strip of odd length
+    fn night_light_pixels(&self) -> Vec<Rgb> {
+        let middle = (self.num_pixels.saturating_sub(1) / 2)..=(self.num_pixels / 2);
+        (0..self.num_pixels)
+            .map(|index| {
+                if middle.contains(&index) {
+                    NIGHT_LIGHT_COLOUR
+                } else {
+                    (0, 0, 0)
+                }
+            })
+            .collect()
+    }
+
+    /// Turn on a dim red light, on a subset of the pixels, has no effect when an alarm is in progress
+    async fn night_light(&mut self, duration: LightDuration) {
+        if self.alarm.is_some() {
+            return;
+        }
+        self.cancel_thead();
+        self.activate(
+            LightMode::NightLight,
+            duration,
+            NIGHT_LIGHT_BRIGHTNESS,
+            NIGHT_LIGHT_COLOUR,
+            Some(self.night_light_pixels()),
+        );
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// Send a render message at a fixed interval, until cancelled
+    async fn render_loop(tx: Sender<LightMsg>, interval_ms: u64) {
+        loop {
+            tx.send(LightMsg::Render).await.ok();
+            sleep!(interval_ms);
+        }
+    }
+
+    /// Spawn a render loop, which is stopped by the next call to `cancel_thead`
+    fn start_render_loop(&mut self, interval_ms: u64) {
+        let (token, tx) = self.get_token_sender();
+        tokio::spawn(async move {
+            token
+                .run_until_cancelled(Self::render_loop(tx, interval_ms))
+                .await;
+        });
+    }
+
+    /// An escalating alarm is rendered as often as an effect, so that each pulse, or flash, is smooth
+    fn alarm_interval(alarm: &AlarmSequence) -> u64 {
+        if alarm.escalating(alarm.elapsed()) {
+            EFFECT_INTERVAL_MS
+        } else {
+            RENDER_INTERVAL_MS
+        }
+    }
+
+    /// Start the alarm sequence, an alarm that is already in progress is left to continue
+    async fn alarm_on(&mut self, sequence: AlarmSequence) {
+        if self.alarm.is_some() {
+            return;
+        }
+        self.cancel_thead();
+        let elapsed = sequence.elapsed();
+        self.mode = LightMode::Alarm {
+            step: sequence.step(elapsed),
+            escalating: sequence.escalating(elapsed),
+        };
+        let interval_ms = Self::alarm_interval(&sequence);
+        let snooze_remaining = sequence.snooze_remaining();
+        self.alarm = Some(sequence.with_num_pixels(self.num_pixels));
+        self.off_at = None;
+        self.pixels = None;
+        self.sunset = None;
+        self.effect = None;
+        // A sequence resumed after a restart may still be snoozed
+        match snooze_remaining {
+            Some(remaining) => self.pause_alarm(remaining),
+            None => self.start_render_loop(interval_ms),
+        }
+        self.alarm_progress().await;
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+        self.msg_tx.send(Msg::StatusFile(Some(()))).await.ok();
+    }
+
+    /// Start a sunset, fading the light to off, an alarm that is already in progress is left to continue
+    async fn sunset_on(&mut self, sequence: SunsetSequence) {
+        if self.alarm.is_some() {
+            return;
+        }
+        self.cancel_thead();
+        self.off_at = None;
+        self.pixels = None;
+        self.effect = None;
+        self.sunset = Some(sequence);
+        self.mode = LightMode::Sunset;
+        self.start_render_loop(RENDER_INTERVAL_MS);
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// Start an effect, replacing whatever the light is currently doing, an alarm that is already in progress is left to continue
+    async fn effect_on(&mut self, settings: EffectSettings) {
+        let mode = LightMode::Effect {
+            effect: settings.name,
+        };
+        self.start_effect(mode, RunningEffect::new(&settings, self.num_pixels))
+            .await;
+    }
+
+    /// Play an animation, either once or on repeat, replacing whatever the light is currently doing, an alarm that is already in progress is left to continue
+    async fn animation_on(&mut self, animation_id: i64, animation: Animation, looping: bool) {
+        let effect = RunningEffect::animation(animation, looping, self.num_pixels);
+        self.start_effect(LightMode::Animation { animation_id }, effect)
+            .await;
+    }
+
+    /// Start rendering a running effect
+    async fn start_effect(&mut self, mode: LightMode, effect: RunningEffect) {
+        if self.alarm.is_some() {
+            return;
+        }
+        self.cancel_thead();
+        self.off_at = None;
+        self.pixels = None;
+        self.sunset = None;
+        self.mode = mode;
+        self.effect = Some(effect);
+        self.start_render_loop(EFFECT_INTERVAL_MS);
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// Stop an effect, or animation, turning the light off, has no effect if an effect isn't running
+    async fn effect_stop(&mut self) {
+        if self.effect.is_some() {
+            self.toggle(None).await;
+        }
+    }
+
+    /// Display a computed frame
+    fn show_frame(&mut self, frame: Frame) {
+        self.brightness = frame.brightness;
+        self.colours = frame.colours;
+        self.pixels = frame.pixels;
+        self.display();
+    }
+
+    /// Recompute, and display, the current alarm or sunset frame, both the brightness and colour are based on the time elapsed since the sequence started
+    /// Turns the light off once the sequence has finished
+    async fn render(&mut self) {
+        if let Some(effect) = &self.effect {
+            match effect.frame() {
+                Some(frame) => {
+                    self.brightness = frame.brightness;
+                    self.pixels = Some(frame.pixels);
+                    self.display();
+                }
+                None => self.toggle(None).await,
+            }
+            return;
+        }
+        if let Some(sunset) = self.sunset {
+            match sunset.frame(sunset.elapsed()) {
+                Some(frame) => self.show_frame(frame),
+                None => self.toggle(None).await,
+            }
+            return;
+        }
+        let Some(alarm) = &self.alarm else {
+            return;
+        };
+        if alarm.snooze_remaining().is_some() {
+            return;
+        }
+        let elapsed = alarm.elapsed();
+        let step = alarm.step(elapsed);
+        let escalating = alarm.escalating(elapsed);
+        match alarm.frame(elapsed) {
+            Some(frame) => {
+                if escalating
+                    && !matches!(
+                        self.mode,
+                        LightMode::Alarm {
+                            escalating: true,
+                            ..
+                        }
+                    )
+                {
+                    self.cancel_thead();
+                    self.start_render_loop(EFFECT_INTERVAL_MS);
+                }
+                let step_changed = !matches!(self.mode, LightMode::Alarm { step: previous, .. } if previous == step);
+                self.mode = LightMode::Alarm { step, escalating };
+                self.show_frame(frame);
+                if step_changed {
+                    self.alarm_progress().await;
+                }
+            }
+            None => {
+                self.end_alarm(AlarmOutcome::TimedOut).await;
+                self.toggle(None).await;
+            }
+        }
+    }
+
+    /// Persist the progress of the current alarm, including any snooze, so that it can be resumed after a restart
+    async fn alarm_progress(&mut self) {
+        if let Some(alarm) = &self.alarm
+            && let Some(alarm_id) = alarm.alarm_id()
+        {
+            self.msg_tx
+                .send(Msg::AlarmProgress(
+                    alarm_id,
+                    alarm.elapsed(),
+                    alarm.snooze_remaining(),
+                ))
+                .await
+                .ok();
+        }
+    }
+
+    /// Clear the current alarm, if there is one, and record how it ended
+    async fn end_alarm(&mut self, outcome: AlarmOutcome) {
+        if let Some(alarm) = self.alarm.take() {
+            self.msg_tx
+                .send(Msg::AlarmOutcome(alarm.alarm_id(), outcome))
+                .await
+                .ok();
+        }
+    }
+
+    /// Pause the alarm sequence, dimming the light to the start of the sequence, and resume it after the given number of minutes
+    /// Has no effect when an alarm isn't in progress
+    async fn snooze(&mut self, minutes: Option<u16>) {
+        let Some(alarm) = &mut self.alarm else {
+            return;
+        };
+        let duration = Duration::from_secs(u64::from(minutes.unwrap_or(SNOOZE_MINUTES)) * 60);
+        alarm.snooze(duration);
+        self.pause_alarm(duration);
+        self.alarm_progress().await;
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// Dim the light to the start of the alarm sequence, and end the snooze after the given duration
+    fn pause_alarm(&mut self, duration: Duration) {
+        if let Some(frame) = self
+            .alarm
+            .as_ref()
+            .and_then(|alarm| alarm.frame(Duration::ZERO))
+        {
+            self.brightness = frame.brightness;
+            self.colours = frame.colours;
+            self.pixels = frame.pixels;
+        }
+        self.cancel_thead();
+        self.display();
+        let (token, tx) = self.get_token_sender();
+        tokio::spawn(async move {
+            token
+                .run_until_cancelled(async move {
+                    tokio::time::sleep(duration).await;
+                    tx.send(LightMsg::SnoozeEnd).await.ok();
+                })
+                .await;
+        });
+    }
+
+    /// End a snooze, restarting the render loop from the point the alarm was paused
+    async fn snooze_end(&mut self) {
+        let Some(alarm) = &mut self.alarm else {
+            return;
+        };
+        alarm.resume();
+        let interval_ms = Self::alarm_interval(alarm);
+        self.cancel_thead();
+        self.start_render_loop(interval_ms);
+        self.alarm_progress().await;
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// End an alarm sequence, has no effect if the light was turned on manually
+    async fn dismiss(&mut self) {
+        if matches!(self.mode, LightMode::Alarm { .. }) {
+            self.end_alarm(AlarmOutcome::Acknowledged).await;
+            self.toggle(None).await;
+        }
+    }
+
+    /// Current status of the light
+    fn led_status(&self) -> LedStatus {
+        LedStatus {
+            status: self.mode != LightMode::Off,
+            mode: self.mode,
+            #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            brightness: (self.brightness.clamp(0.0, 1.0) * 100.0).round() as u8,
+            colour: self.colours,
+            pixels: C!(self.pixels),
+            snooze_remaining: self
+                .alarm
+                .as_ref()
+                .and_then(AlarmSequence::snooze_remaining)
+                .map(|remaining| remaining.as_secs()),
+            off_remaining: self
+                .off_at
+                .map(|off_at| off_at.saturating_duration_since(Instant::now()))
+                .or_else(|| self.sunset.map(|sunset| sunset.remaining()))
+                .map(|remaining| remaining.as_secs()),
+            health: self.renderer.health(),
+            render: self.renderer.stats(),
+        }
+    }
+
+    /// Toggle the status of the blinkt, `None` turns it off
+    async fn toggle(&mut self, settings: Option<LightSettings>) {
+        self.cancel_thead();
+        if let Some(settings) = settings {
+            self.turn_on(settings);
+        } else {
+            self.turn_off().await;
+        }
+        self.msg_tx.send(Msg::SendLEDStatus).await.ok();
+    }
+
+    /// Message listener
+    async fn recv(&mut self, rx: Receiver<LightMsg>) {
+        loop {
+            if let Ok(x) = rx.recv().await {
+                match x {
+                    LightMsg::Alarm(sequence) => self.alarm_on(sequence).await,
+                    LightMsg::Animation(animation_id, animation, looping) => {
+                        self.animation_on(animation_id, animation, looping).await;
+                    }
+                    LightMsg::Calibration(settings) => self.calibrate(settings),
+                    LightMsg::CalibrationPreview(settings, colour) => {
+                        self.calibration_preview(settings, colour).await;
+                    }
+                    LightMsg::CalibrationPreviewEnd => self.calibration_preview_end(),
+                    LightMsg::Dismiss => self.dismiss().await,
+                    LightMsg::Effect(settings) => self.effect_on(settings).await,
+                    LightMsg::EffectStop => self.effect_stop().await,
+                    LightMsg::Exit => self.turn_off().await,
+                    LightMsg::Get(oneshot) => {
+                        oneshot.send(self.led_status()).await.unwrap_or_default();
+                    }
+                    LightMsg::NightLight(duration) => self.night_light(duration).await,
+                    LightMsg::Off => {
+                        self.end_alarm(AlarmOutcome::Acknowledged).await;
+                        self.toggle(None).await;
+                    }
+                    LightMsg::On(settings) => {
+                        self.end_alarm(AlarmOutcome::Acknowledged).await;
+                        self.toggle(Some(settings)).await;
+                    }
+                    LightMsg::Render => self.render().await,
+                    LightMsg::Scene(scene_id, settings) => {
+                        self.end_alarm(AlarmOutcome::Acknowledged).await;
+                        self.scene(scene_id, settings).await;
+                    }
+                    LightMsg::Snooze(minutes) => self.snooze(minutes).await,
+                    LightMsg::SnoozeEnd => self.snooze_end().await,
+                    LightMsg::Sunset(sequence) => self.sunset_on(sequence).await,
+                }
+            }
+        }
+    }
+
+    /// Create the blinkt for the configured output and strip, called again by the supervisor whenever the blinkt is missing
+    fn connect(led_output: LedOutput, led_strip: LedStrip) -> Connect {
+        let num_pixels = led_strip.num_pixels;
+        let power_budget_ma = led_strip.power_budget_ma;
+        let mut connect: Connect = match led_output {
+            LedOutput::Hardware => Box::new(move || match led_strip.transport {
+                LedTransport::Bitbang {
+                    pin_data,
+                    pin_clock,
+                } => blinkt::Blinkt::with_settings(pin_data, pin_clock, num_pixels),
+                LedTransport::Spi {
+                    bus,
+                    clock_speed_hz,
+                } => blinkt::BlinktSpi::with_bus(bus, clock_speed_hz)
+                    .map(|spi| blinkt::Blinkt::with_spi(spi, num_pixels)),
+            }),
+            LedOutput::Recording => {
+                tracing::info!("Recording LED output, no Blinkt will be used");
+                Box::new(move || {
+                    Ok(blinkt::Blinkt::with_output(
+                        blinkt::RecordingOutput::new(num_pixels),
+                        num_pixels,
+                    ))
+                })
+            }
+            LedOutput::Terminal => {
+                tracing::info!("Terminal LED output, no Blinkt will be used");
+                Box::new(move || {
+                    Ok(blinkt::Blinkt::with_output(
+                        blinkt::TerminalOutput::new(num_pixels),
+                        num_pixels,
+                    ))
+                })
+            }
+        };
+        Box::new(move || {
+            connect().map(|mut blinkt| {
+                blinkt.set_power_budget(power_budget_ma);
+                blinkt
+            })
+        })
+    }
+
+    /// Start the receiving channel
+    pub fn init(msg_tx: &Sender<Msg>, app_env: &AppEnv) -> Sender<LightMsg> {
+        let (tx, rx) = async_channel::bounded(128);
+        let mut light_control = Self::new(
+            msg_tx,
+            &tx,
+            Self::connect(app_env.led_output, app_env.led_strip),
+            app_env.led_strip.num_pixels,
+        );
+        tokio::spawn(async move {
+            light_control.recv(rx).await;
+        });
+        tx
+    }
+}
+
+/// LightControl tests, frames are captured by a recording output
+///
+/// cargo watch -q -c -w src/ -x 'test light_control -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::blinkt::{Recording, RecordingOutput};
+    use supervisor::connect_once;
+
+    const START_FRAME: [u8; 4] = [0; 4];
+    const END_FRAME: [u8; 5] = [0; 5];
+
+    fn setup() -> (LightControl, Recording, Receiver<Msg>) {
+        setup_strip(blinkt::NUM_PIXELS)
+    }
+
+    fn setup_strip(num_pixels: usize) -> (LightControl, Recording, Receiver<Msg>) {
+        let (msg_tx, msg_rx) = async_channel::unbounded();
+        let (tx, _) = async_channel::bounded(128);
+        let output = RecordingOutput::new(num_pixels);
+        let recording = output.recording();
+        let mut blinkt = blinkt::Blinkt::with_output(output, num_pixels);
+        blinkt.set_clear_on_drop(false);
+        (
+            LightControl::new(&msg_tx, &tx, connect_once(blinkt), num_pixels),
+            recording,
+            msg_rx,
+        )
+    }
+
+    /// Frames are written on the render thread, so wait for them to arrive
+    async fn wait_frames(recording: &Recording, frames: usize) {
+        let start = Instant::now();
+        while recording.len() < frames {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            sleep!(1);
+        }
+    }
+
+    /// Build the exact bytes expected for a frame, from each pixel's brightness, blue, green, red bytes
+    fn frame(pixels: &[[u8; 4]]) -> Vec<u8> {
+        let mut frame = START_FRAME.to_vec();
+        for pixel in pixels {
+            frame.extend_from_slice(pixel);
+        }
+        frame.extend_from_slice(&END_FRAME);
+        frame
+    }
+
+    #[tokio::test]
+    async fn light_control_on_off() {
+        let (mut light_control, recording, _msg_rx) = setup();
+
+        light_control.turn_on(LightSettings {
+            colour: Some((255, 0, 0)),
+            duration: Some(LightDuration::Never),
+            ..LightSettings::default()
+        });
+        wait_frames(&recording, 1).await;
+        assert_eq!(
+            recording.frames(),
+            vec![frame(&[[0b1111_1111, 0, 0, 255]; blinkt::NUM_PIXELS])]
+        );
+
+        light_control.turn_off().await;
+        wait_frames(&recording, 2).await;
+        assert_eq!(
+            recording.last().unwrap(),
+            frame(&[[0b1110_0000, 0, 0, 0]; blinkt::NUM_PIXELS])
+        );
+    }
+
+    #[tokio::test]
+    async fn light_control_pixels() {
+        let (mut light_control, recording, _msg_rx) = setup();
+
+        light_control.turn_on(LightSettings {
+            pixels: Some(vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)]),
+            duration: Some(LightDuration::Never),
+            ..LightSettings::default()
+        });
+
+        let mut expected = [[0b1111_1111, 0, 0, 0]; blinkt::NUM_PIXELS];
+        expected[0] = [0b1111_1111, 0, 0, 255];
+        expected[1] = [0b1111_1111, 0, 255, 0];
+        expected[2] = [0b1111_1111, 255, 0, 0];
+        wait_frames(&recording, 1).await;
+        assert_eq!(recording.last().unwrap(), frame(&expected));
+        light_control.turn_off().await;
+    }
+
+    #[tokio::test]
+    async fn light_control_calibration() {
+        let (mut light_control, recording, _msg_rx) = setup();
+
+        light_control.turn_on(LightSettings {
+            colour: Some((255, 255, 255)),
+            duration: Some(LightDuration::Never),
+            ..LightSettings::default()
+        });
+        wait_frames(&recording, 1).await;
+        light_control.calibrate(CalibrationSettings {
+            white_point: (255, 180, 200),
+            ..CalibrationSettings::default()
+        });
+        wait_frames(&recording, 2).await;
+
+        assert_eq!(
+            recording.frames(),
+            vec![
+                frame(&[[0b1111_1111, 255, 255, 255]; blinkt::NUM_PIXELS]),
+                frame(&[[0b1111_1111, 200, 180, 255]; blinkt::NUM_PIXELS]),
+            ]
+        );
+        light_control.turn_off().await;
+    }
+
+    /// The most recent alarm progress sent
+    fn alarm_progress(msg_rx: &Receiver<Msg>) -> Option<(i64, Duration, Option<Duration>)> {
+        let mut progress = None;
+        while let Ok(msg) = msg_rx.try_recv() {
+            if let Msg::AlarmProgress(alarm_id, elapsed, snooze) = msg {
+                progress = Some((alarm_id, elapsed, snooze));
+            }
+        }
+        progress
+    }
+
+    #[tokio::test]
+    async fn light_control_snooze_progress() {
+        let (mut light_control, _recording, msg_rx) = setup();
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(60), Some(0))
+            .with_alarm_id(1)
+            .with_elapsed(Duration::from_secs(30 * 60));
+
+        light_control.alarm_on(sequence).await;
+        let (alarm_id, _, snooze) = alarm_progress(&msg_rx).unwrap();
+        assert_eq!(alarm_id, 1);
+        assert!(snooze.is_none());
+
+        // Starting a snooze saves the paused progress, and the end of the snooze
+        light_control.snooze(Some(10)).await;
+        let (_, elapsed, snooze) = alarm_progress(&msg_rx).unwrap();
+        assert!(elapsed >= Duration::from_secs(30 * 60) && elapsed < Duration::from_secs(31 * 60));
+        assert!(snooze.unwrap() > Duration::from_secs(9 * 60));
+        light_control.turn_off().await;
+    }
+
+    #[tokio::test]
+    async fn light_control_alarm_resume_snoozed() {
+        let (mut light_control, _recording, msg_rx) = setup();
+        let mut sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(60), Some(0))
+            .with_alarm_id(1)
+            .with_elapsed(Duration::from_secs(30 * 60));
+        sequence.snooze(Duration::from_secs(5 * 60));
+
+        light_control.alarm_on(sequence).await;
+
+        // Still snoozed, dimmed to the start of the sequence
+        let status = light_control.led_status();
+        assert!(status.snooze_remaining.unwrap() > 4 * 60);
+        assert_eq!(status.brightness, 0);
+        let (_, elapsed, snooze) = alarm_progress(&msg_rx).unwrap();
+        assert!(elapsed >= Duration::from_secs(30 * 60) && elapsed < Duration::from_secs(31 * 60));
+        assert!(snooze.is_some());
+        light_control.turn_off().await;
+    }
+
+    #[tokio::test]
+    async fn light_control_night_light_strip_length() {
+        let off = (0, 0, 0);
+        let on = NIGHT_LIGHT_COLOUR;
+        for (num_pixels, expected) in [
+            (1, vec![on]),
+            (2, vec![on, on]),
+            (5, vec![off, off, on, off, off]),
+            (8, vec![off, off, off, on, on, off, off, off]),
+        ] {
+            let (light_control, _, _msg_rx) = setup_strip(num_pixels);
+            assert_eq!(light_control.night_light_pixels(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn light_control_effect_strip_length() {
+        let (mut light_control, recording, _msg_rx) = setup_strip(30);
+
+        light_control
+            .effect_on(EffectSettings {
+                name: EffectName::Rainbow,
+                colour: None,
+                brightness: None,
+                period_ms: None,
+            })
+            .await;
+        light_control.render().await;
+        wait_frames(&recording, 1).await;
+
+        let frame = recording.last().unwrap();
+        let decoded = blinkt::decode::decode(&frame, 30).unwrap();
+        assert_eq!(decoded.pixels.len(), 30);
+        assert!(decoded.sk9822_compatible);
+        light_control.turn_off().await;
+    }
+}
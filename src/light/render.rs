@@ -0,0 +1,518 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use async_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+
+use super::supervisor::{Connect, LedHealth, Supervisor};
+use crate::{
+    C,
+    blinkt::{Calibration, CalibrationSettings, Pixel},
+    message_handler::Msg,
+};
+
+/// Frames waiting to be written, once full the oldest waiting frame is replaced
+const FRAME_BUFFER: usize = 2;
+
+/// Longest wait between checks for new frames whilst the blinkt is missing
+const MISSING_POLL: Duration = Duration::from_secs(1);
+
+/// Longest time without a write before the current frame is written again, to recover a strip corrupted by a glitch
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the frame timing statistics are logged
+const STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A complete frame, and the calibration to show it with
+#[derive(Debug, Clone)]
+struct RenderFrame {
+    calibration: CalibrationSettings,
+    pixels: Vec<Pixel>,
+}
+
+/// Frame timing statistics, since the render thread started, times are in microseconds
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct RenderStats {
+    /// Frames written to the strip
+    pub frames: u64,
+    /// Frames replaced by a newer frame before they were written
+    pub coalesced: u64,
+    /// Frames that failed to write
+    pub errors: u64,
+    /// Frames not written, as they were identical to the frame already on the strip
+    pub skipped: u64,
+    pub last_write_us: u64,
+    pub max_write_us: u64,
+    pub mean_write_us: u64,
+    /// Estimated current drawn by the last frame written, in milliamps
+    pub estimated_ma: u32,
+    /// Budget the estimated current is kept within, in milliamps, `None` when unlimited
+    pub power_budget_ma: Option<u32>,
+    #[serde(skip)]
+    total_write_us: u64,
+}
+
+impl RenderStats {
+    fn record(&mut self, write: Duration, ok: bool) {
+        let write_us = u64::try_from(write.as_micros()).unwrap_or(u64::MAX);
+        self.frames += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.last_write_us = write_us;
+        self.max_write_us = self.max_write_us.max(write_us);
+        self.total_write_us = self.total_write_us.saturating_add(write_us);
+        self.mean_write_us = self.total_write_us / self.frames;
+    }
+}
+
+/// Latest statistics and health, shared with the render thread
+#[derive(Debug, Default)]
+struct RenderState {
+    health: LedHealth,
+    stats: RenderStats,
+}
+
+/// Handle to a dedicated render thread, which owns the blinkt, so that slow writes, such as GPIO bit-banging, never block the async runtime
+/// The thread exits, and clears the blinkt, once the handle is dropped
+pub struct Renderer {
+    calibration: CalibrationSettings,
+    state: Arc<Mutex<RenderState>>,
+    tx: Sender<RenderFrame>,
+}
+
+impl Renderer {
+    /// Start the render thread, the blinkt is initialised on the thread, and again whenever it goes missing
+    pub fn new(connect: Connect, msg_tx: &Sender<Msg>) -> Self {
+        Self::with_refresh(connect, msg_tx, REFRESH_INTERVAL)
+    }
+
+    /// Start the render thread, rewriting the current frame whenever nothing has been written for `refresh`
+    fn with_refresh(connect: Connect, msg_tx: &Sender<Msg>, refresh: Duration) -> Self {
+        let (tx, rx) = async_channel::bounded(FRAME_BUFFER);
+        let state = Arc::new(Mutex::new(RenderState::default()));
+        let thread_state = Arc::clone(&state);
+        let msg_tx = C!(msg_tx);
+        std::thread::Builder::new()
+            .name("render".into())
+            .spawn(move || {
+                Self::run(
+                    Supervisor::new(connect),
+                    &rx,
+                    &thread_state,
+                    &msg_tx,
+                    refresh,
+                );
+            })
+            .map_err(|e| tracing::error!("Unable to start render thread: {e}"))
+            .ok();
+        Self {
+            calibration: CalibrationSettings::default(),
+            state,
+            tx,
+        }
+    }
+
+    /// Write every frame received, any frames that arrive whilst a frame is being written are coalesced into the newest
+    /// Whilst the blinkt is missing, frames are held, and the newest is written as soon as it has been initialised again
+    /// The frame is always sent after the blinkt is initialised again, or when nothing has been written for `refresh`, even if unchanged
+    fn run(
+        mut supervisor: Supervisor,
+        rx: &Receiver<RenderFrame>,
+        state: &Mutex<RenderState>,
+        msg_tx: &Sender<Msg>,
+        refresh: Duration,
+    ) {
+        // Only used to stop waiting for the next frame once it is time to refresh the strip
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .map_err(|e| {
+                tracing::error!("Unable to start render timer, the strip won't be refreshed: {e}")
+            })
+            .ok();
+        let mut logged_at = Instant::now();
+        let mut frame: Option<RenderFrame> = None;
+        let mut pending = false;
+        let mut force = false;
+        let mut health = supervisor.health();
+        state.lock().unwrap_or_else(PoisonError::into_inner).health = health;
+
+        loop {
+            let mut coalesced = 0;
+            let mut skipped = 0;
+            if let Some(wait) = supervisor.retry_in() {
+                std::thread::sleep(wait.min(MISSING_POLL));
+                if rx.is_closed() && rx.is_empty() {
+                    break;
+                }
+                if supervisor.try_connect() {
+                    pending = frame.is_some();
+                    force = true;
+                }
+            } else {
+                let next = match &runtime {
+                    Some(runtime) => {
+                        runtime.block_on(async { tokio::time::timeout(refresh, rx.recv()).await })
+                    }
+                    None => Ok(rx.recv_blocking()),
+                };
+                match next {
+                    Ok(Ok(next)) => {
+                        if pending {
+                            coalesced += 1;
+                        }
+                        frame = Some(next);
+                        pending = true;
+                    }
+                    Ok(Err(_)) => break,
+                    Err(_) => {
+                        pending = frame.is_some();
+                        force = true;
+                    }
+                }
+            }
+            while let Ok(next) = rx.try_recv() {
+                if pending {
+                    coalesced += 1;
+                }
+                frame = Some(next);
+                pending = true;
+            }
+
+            let mut written = None;
+            if pending
+                && let Some(frame) = &frame
+                && let Some(blinkt) = supervisor.blinkt()
+            {
+                if blinkt.calibration().settings() != frame.calibration {
+                    blinkt.set_calibration(Calibration::new(frame.calibration));
+                }
+                for (pixel, value) in blinkt.iter_mut().zip(&frame.pixels) {
+                    *pixel = *value;
+                }
+                let start = Instant::now();
+                let result = if force {
+                    blinkt.force_show().map(|()| true)
+                } else {
+                    blinkt.show()
+                };
+                let write = start.elapsed();
+                if let Err(e) = &result {
+                    tracing::error!("Unable to write frame: {e}");
+                }
+                let power = (blinkt.estimated_ma(), blinkt.power_budget());
+                pending = false;
+                force = false;
+                supervisor.record(result.is_ok());
+                if matches!(result, Ok(false)) {
+                    skipped += 1;
+                } else {
+                    written = Some((write, result.is_ok(), power));
+                }
+            }
+
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.stats.coalesced += coalesced;
+            state.stats.skipped += skipped;
+            if let Some((write, ok, (estimated_ma, power_budget_ma))) = written {
+                state.stats.record(write, ok);
+                #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let estimated_ma = estimated_ma.round() as u32;
+                state.stats.estimated_ma = estimated_ma;
+                state.stats.power_budget_ma = power_budget_ma;
+            }
+            if supervisor.health() != health {
+                health = supervisor.health();
+                state.health = health;
+                msg_tx.send_blocking(Msg::LedHealth(health)).ok();
+            }
+            if logged_at.elapsed() >= STATS_INTERVAL {
+                logged_at = Instant::now();
+                let stats = state.stats;
+                tracing::debug!(
+                    "frames: {}, coalesced: {}, errors: {}, skipped: {}, mean write: {}us, max write: {}us",
+                    stats.frames,
+                    stats.coalesced,
+                    stats.errors,
+                    stats.skipped,
+                    stats.mean_write_us,
+                    stats.max_write_us
+                );
+            }
+        }
+    }
+
+    /// Set the calibration used from the next frame
+    pub const fn set_calibration(&mut self, calibration: CalibrationSettings) {
+        self.calibration = calibration;
+    }
+
+    /// Queue a frame to be written, never waits, if the render thread is behind the oldest waiting frame is replaced
+    pub fn show(&self, pixels: Vec<Pixel>) {
+        let frame = RenderFrame {
+            calibration: self.calibration,
+            pixels,
+        };
+        if let Ok(Some(_)) = self.tx.force_send(frame) {
+            self.state
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .stats
+                .coalesced += 1;
+        }
+    }
+
+    /// A snapshot of the frame timing statistics
+    pub fn stats(&self) -> RenderStats {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .stats
+    }
+
+    /// Whether the LED hardware is working
+    pub fn health(&self) -> LedHealth {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .health
+    }
+}
+
+/// Render thread tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_render -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::{
+        blinkt::{self, Blinkt, RecordingOutput, SerialOutput},
+        light::supervisor::connect_once,
+    };
+
+    /// Output that takes a long time to write each frame, so that frames back up
+    struct SlowOutput(RecordingOutput);
+
+    impl SerialOutput for SlowOutput {
+        fn write(&mut self, data: &[u8]) -> blinkt::Result<()> {
+            std::thread::sleep(Duration::from_millis(10));
+            self.0.write(data)
+        }
+
+        fn flush(&mut self) -> blinkt::Result<()> {
+            self.0.flush()
+        }
+    }
+
+    /// Output that always fails to write
+    struct FailingOutput;
+
+    impl SerialOutput for FailingOutput {
+        fn write(&mut self, _: &[u8]) -> blinkt::Result<()> {
+            Err(blinkt::Error::Io(io::Error::other("write failed")))
+        }
+    }
+
+    fn renderer(blinkt: Blinkt) -> (Renderer, Receiver<Msg>) {
+        let (msg_tx, msg_rx) = async_channel::unbounded();
+        (Renderer::new(connect_once(blinkt), &msg_tx), msg_rx)
+    }
+
+    fn pixel(red: u8) -> Pixel {
+        let mut pixel = Pixel::default();
+        pixel.set_rgb(red, 0, 0);
+        pixel
+    }
+
+    /// Wait until the render thread has written a given number of frames
+    fn wait_frames(renderer: &Renderer, frames: u64) {
+        let start = Instant::now();
+        while renderer.stats().frames < frames {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn light_render_show() {
+        let output = RecordingOutput::new(2);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 2);
+        blinkt.set_clear_on_drop(false);
+        let (mut renderer, _msg_rx) = renderer(blinkt);
+
+        renderer.show(vec![pixel(1), pixel(2)]);
+        wait_frames(&renderer, 1);
+        let decoded = blinkt::decode::decode(&recording.last().unwrap(), 2).unwrap();
+        assert_eq!(decoded.pixels, vec![pixel(1), pixel(2)]);
+
+        // Calibration is sent with each frame
+        let calibration = CalibrationSettings {
+            white_point: (128, 255, 255),
+            ..CalibrationSettings::default()
+        };
+        renderer.set_calibration(calibration);
+        renderer.show(vec![pixel(255), pixel(0)]);
+        wait_frames(&renderer, 2);
+        let decoded = blinkt::decode::decode(&recording.last().unwrap(), 2).unwrap();
+        assert_eq!(decoded.pixels, vec![pixel(128), pixel(0)]);
+
+        let stats = renderer.stats();
+        assert_eq!(stats.frames, 2);
+        assert_eq!(stats.coalesced, 0);
+        assert_eq!(stats.errors, 0);
+        assert!(stats.max_write_us >= stats.mean_write_us);
+    }
+
+    #[test]
+    fn light_render_power_budget() {
+        let output = RecordingOutput::new(2);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 2);
+        blinkt.set_clear_on_drop(false);
+        blinkt.set_power_budget(Some(6));
+        let (renderer, _msg_rx) = renderer(blinkt);
+
+        renderer.show(vec![pixel(255), pixel(255)]);
+        wait_frames(&renderer, 1);
+        let decoded = blinkt::decode::decode(&recording.last().unwrap(), 2).unwrap();
+        let (red, _, _) = decoded.pixels[0].rgb();
+        assert!(red < 255);
+        assert_eq!(decoded.pixels[0], decoded.pixels[1]);
+
+        let stats = renderer.stats();
+        assert!(stats.estimated_ma > 0);
+        assert!(stats.estimated_ma <= 6);
+        assert_eq!(stats.power_budget_ma, Some(6));
+    }
+
+    #[test]
+    fn light_render_skip_unchanged() {
+        let output = RecordingOutput::new(1);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 1);
+        blinkt.set_clear_on_drop(false);
+        let (renderer, _msg_rx) = renderer(blinkt);
+
+        renderer.show(vec![pixel(1)]);
+        wait_frames(&renderer, 1);
+        renderer.show(vec![pixel(1)]);
+        let start = Instant::now();
+        while renderer.stats().skipped < 1 {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let stats = renderer.stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(recording.len(), 1);
+    }
+
+    #[test]
+    fn light_render_refresh() {
+        let output = RecordingOutput::new(1);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(output, 1);
+        blinkt.set_clear_on_drop(false);
+        let (msg_tx, _msg_rx) = async_channel::unbounded();
+        let renderer =
+            Renderer::with_refresh(connect_once(blinkt), &msg_tx, Duration::from_millis(20));
+
+        renderer.show(vec![pixel(1)]);
+
+        // The unchanged frame is written again whilst no new frames arrive
+        wait_frames(&renderer, 3);
+        let frames = recording.frames();
+        assert!(frames.len() >= 3);
+        assert!(frames.iter().all(|frame| *frame == frames[0]));
+        assert_eq!(renderer.stats().skipped, 0);
+    }
+
+    #[test]
+    fn light_render_coalesce() {
+        let output = RecordingOutput::new(1);
+        let recording = output.recording();
+        let mut blinkt = Blinkt::with_output(SlowOutput(output), 1);
+        blinkt.set_clear_on_drop(false);
+        let (renderer, _msg_rx) = renderer(blinkt);
+
+        for red in 1..=20 {
+            renderer.show(vec![pixel(red)]);
+        }
+
+        // Every frame is either written or coalesced
+        let start = Instant::now();
+        while renderer.stats().frames + renderer.stats().coalesced < 20 {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        // The newest frame is always written
+        let stats = renderer.stats();
+        assert!(stats.coalesced > 0);
+        assert_eq!(stats.frames + stats.coalesced, 20);
+        assert_eq!(usize::try_from(stats.frames).unwrap(), recording.len());
+        assert_eq!(recording.last().unwrap()[7], 20);
+        assert!(stats.max_write_us >= 30_000);
+    }
+
+    #[test]
+    fn light_render_clear_on_drop() {
+        let output = RecordingOutput::new(1);
+        let recording = output.recording();
+        let (renderer, _msg_rx) = renderer(Blinkt::with_output(output, 1));
+
+        renderer.show(vec![pixel(200)]);
+        wait_frames(&renderer, 1);
+        drop(renderer);
+
+        let start = Instant::now();
+        while recording.len() < 2 {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(recording.last().unwrap()[4..8], [0b1110_0111, 0, 0, 0]);
+    }
+
+    #[test]
+    fn light_render_health_degraded() {
+        let mut blinkt = Blinkt::with_output(FailingOutput, 1);
+        blinkt.set_clear_on_drop(false);
+        let (renderer, msg_rx) = renderer(blinkt);
+        let start = Instant::now();
+        while renderer.health() != LedHealth::Ok {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        renderer.show(vec![pixel(1)]);
+        wait_frames(&renderer, 1);
+        assert_eq!(renderer.health(), LedHealth::Degraded);
+        assert_eq!(renderer.stats().errors, 1);
+        assert!(matches!(
+            msg_rx.recv_blocking().unwrap(),
+            Msg::LedHealth(LedHealth::Degraded)
+        ));
+    }
+
+    #[test]
+    fn light_render_health_missing() {
+        let (msg_tx, _msg_rx) = async_channel::unbounded();
+        let connect: Connect = Box::new(|| Err(blinkt::Error::Io(io::Error::other("not found"))));
+        let renderer = Renderer::new(connect, &msg_tx);
+
+        renderer.show(vec![pixel(1)]);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(renderer.health(), LedHealth::Missing);
+        assert_eq!(renderer.stats().frames, 0);
+    }
+}
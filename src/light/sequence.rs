@@ -0,0 +1,382 @@
+use std::time::{Duration, Instant};
+
+use super::{
+    Animation, BrightnessCurve, Escalation, Rgb, escalation::ESCALATION_MINUTES, gradient,
+};
+use crate::blinkt;
+
+/// Default minutes taken to go from off to full brightness
+pub const RAMP_MINUTES: u16 = 90;
+
+/// Default minutes the light stays at full brightness once the ramp has finished
+pub const HOLD_MINUTES: u16 = 45;
+
+/// Lowest alarm brightness, so that the light is visibly on from the very start of the sequence
+const MIN_BRIGHTNESS: f32 = 1.0 / 255.0;
+
+/// A single computed state of the light
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub brightness: f32,
+    pub colours: (u8, u8, u8),
+    /// Individual pixel colours, `None` when every pixel is set to `colours`
+    pub pixels: Option<Vec<Rgb>>,
+}
+
+/// An in-progress alarm sequence, every frame is computed from the time elapsed since it started
+#[derive(Debug, Clone)]
+pub struct AlarmSequence {
+    /// Id of the stored alarm that started the sequence
+    alarm_id: Option<i64>,
+    curve: BrightnessCurve,
+    /// Optional stage after the hold, and how long it lasts for
+    escalation: Option<(Escalation, Duration)>,
+    hold: Duration,
+    /// Length of the strip, used when an animation profile is set
+    num_pixels: usize,
+    /// Animation used instead of the curve and sunrise gradient, stretched to fit the ramp
+    profile: Option<Animation>,
+    ramp: Duration,
+    snooze: Option<(Instant, Instant)>,
+    started: Instant,
+}
+
+impl AlarmSequence {
+    /// Create a sequence starting now, a `None` ramp or hold uses the default number of minutes
+    pub fn new(
+        curve: BrightnessCurve,
+        ramp_minutes: Option<u16>,
+        hold_minutes: Option<u16>,
+    ) -> Self {
+        let as_duration = |minutes: u16| Duration::from_secs(u64::from(minutes) * 60);
+        Self {
+            alarm_id: None,
+            curve,
+            escalation: None,
+            hold: as_duration(hold_minutes.unwrap_or(HOLD_MINUTES)),
+            num_pixels: blinkt::NUM_PIXELS,
+            profile: None,
+            ramp: as_duration(ramp_minutes.unwrap_or(RAMP_MINUTES)),
+            snooze: None,
+            started: Instant::now(),
+        }
+    }
+
+    /// Use an animation as the sunrise profile
+    pub fn with_profile(mut self, profile: Option<Animation>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Scale the animation profile to a strip of a given length
+    pub const fn with_num_pixels(mut self, num_pixels: usize) -> Self {
+        self.num_pixels = num_pixels;
+        self
+    }
+
+    /// Record the stored alarm that started the sequence
+    pub const fn with_alarm_id(mut self, alarm_id: i64) -> Self {
+        self.alarm_id = Some(alarm_id);
+        self
+    }
+
+    /// Pulse, or flash, the light once the hold has finished, a `None` minutes uses the default maximum
+    pub fn with_escalation(mut self, escalation: Option<Escalation>, minutes: Option<u16>) -> Self {
+        self.escalation = escalation.map(|escalation| {
+            let minutes = minutes.unwrap_or(ESCALATION_MINUTES);
+            (escalation, Duration::from_secs(u64::from(minutes) * 60))
+        });
+        self
+    }
+
+    /// Move the start of the sequence into the past, used to resume a sequence after a restart
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.started = Instant::now().checked_sub(elapsed).unwrap_or(self.started);
+        self
+    }
+
+    /// Whether the sequence has finished, including any escalation, at a given elapsed time
+    pub fn finished(&self, elapsed: Duration) -> bool {
+        self.frame(elapsed).is_none()
+    }
+
+    pub const fn alarm_id(&self) -> Option<i64> {
+        self.alarm_id
+    }
+
+    /// Whether the sequence is in the escalation stage at a given elapsed time
+    pub fn escalating(&self, elapsed: Duration) -> bool {
+        self.escalation.is_some_and(|(_, limit)| {
+            (self.ramp + self.hold..self.ramp + self.hold + limit).contains(&elapsed)
+        })
+    }
+
+    /// Time since the sequence was started, excluding any time spent snoozing
+    pub fn elapsed(&self) -> Duration {
+        self.snooze.map_or_else(
+            || self.started.elapsed(),
+            |(snoozed_at, _)| snoozed_at.saturating_duration_since(self.started),
+        )
+    }
+
+    /// Pause the sequence for a given duration, snoozing again whilst already snoozed extends the current snooze
+    pub fn snooze(&mut self, duration: Duration) {
+        let now = Instant::now();
+        let snoozed_at = self.snooze.map_or(now, |(snoozed_at, _)| snoozed_at);
+        self.snooze = Some((snoozed_at, now + duration));
+    }
+
+    /// Resume a snoozed sequence at the point it was paused
+    pub fn resume(&mut self) {
+        if let Some((snoozed_at, _)) = self.snooze.take() {
+            self.started += snoozed_at.elapsed();
+        }
+    }
+
+    /// Time left until a snooze ends, `None` if not snoozed
+    pub fn snooze_remaining(&self) -> Option<Duration> {
+        self.snooze
+            .map(|(_, until)| until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Progress through the ramp, from `0.0` to `1.0`
+    pub fn progress(&self, elapsed: Duration) -> f32 {
+        if self.ramp.is_zero() {
+            return 1.0;
+        }
+        (elapsed.as_secs_f32() / self.ramp.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    /// The alarm step, from `1` to `10`, for a given elapsed time
+    pub fn step(&self, elapsed: Duration) -> u8 {
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let step = (self.progress(elapsed) * 10.0).floor() as u8 + 1;
+        step.min(10)
+    }
+
+    /// Compute the frame for a given elapsed time, `None` once the ramp, hold, and any escalation have finished
+    pub fn frame(&self, elapsed: Duration) -> Option<Frame> {
+        let escalation_start = self.ramp + self.hold;
+        if elapsed >= escalation_start {
+            let (escalation, limit) = self.escalation?;
+            if elapsed >= escalation_start + limit {
+                return None;
+            }
+            let mut frame = self.frame_at_progress(1.0);
+            frame.brightness *= escalation.level(elapsed - escalation_start);
+            return Some(frame);
+        }
+        Some(self.frame_at_progress(self.progress(elapsed)))
+    }
+
+    /// Compute the frame at a given progress through the ramp
+    fn frame_at_progress(&self, progress: f32) -> Frame {
+        if let Some(profile) = &self.profile {
+            let frame = profile.frame_at_progress(progress, self.num_pixels);
+            return Frame {
+                brightness: frame.brightness.max(MIN_BRIGHTNESS),
+                colours: frame.pixels.first().copied().unwrap_or_default(),
+                pixels: Some(frame.pixels),
+            };
+        }
+        Frame {
+            brightness: self.curve.apply(progress).max(MIN_BRIGHTNESS),
+            colours: gradient::colour_at(&gradient::SUNRISE, progress),
+            pixels: None,
+        }
+    }
+}
+
+/// AlarmSequence tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_sequence -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const fn minutes(x: u64) -> Duration {
+        Duration::from_secs(x * 60)
+    }
+
+    #[test]
+    fn light_sequence_progress() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, None, None);
+        assert!(sequence.progress(Duration::ZERO).abs() < f32::EPSILON);
+        assert!((sequence.progress(minutes(45)) - 0.5).abs() < f32::EPSILON);
+        assert!((sequence.progress(minutes(90)) - 1.0).abs() < f32::EPSILON);
+        assert!((sequence.progress(minutes(120)) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn light_sequence_frame_start() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Gamma, None, None);
+        let frame = sequence.frame(Duration::ZERO).unwrap();
+        assert!((frame.brightness - MIN_BRIGHTNESS).abs() < f32::EPSILON);
+        assert_eq!(frame.colours, gradient::SUNRISE[0].colour);
+    }
+
+    #[test]
+    fn light_sequence_frame_every_second() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, None, None);
+        let first = sequence.frame(Duration::from_secs(600)).unwrap();
+        let second = sequence.frame(Duration::from_secs(601)).unwrap();
+        assert!(second.brightness > first.brightness);
+    }
+
+    #[test]
+    fn light_sequence_frame_hold() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Exponential, None, None);
+        for elapsed in [minutes(90), minutes(100), minutes(134)] {
+            let frame = sequence.frame(elapsed).unwrap();
+            assert!((frame.brightness - 1.0).abs() < f32::EPSILON);
+            assert_eq!(frame.colours, gradient::SUNRISE[4].colour);
+        }
+    }
+
+    #[test]
+    fn light_sequence_step() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, None, None);
+        assert_eq!(sequence.step(Duration::ZERO), 1);
+        assert_eq!(sequence.step(minutes(8)), 1);
+        assert_eq!(sequence.step(minutes(9)), 2);
+        assert_eq!(sequence.step(minutes(85)), 10);
+        assert_eq!(sequence.step(minutes(120)), 10);
+    }
+
+    #[test]
+    fn light_sequence_custom_duration() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(20), Some(0));
+        assert!((sequence.progress(minutes(10)) - 0.5).abs() < f32::EPSILON);
+        assert!(sequence.frame(minutes(19)).is_some());
+        assert!(sequence.frame(minutes(20)).is_none());
+
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(60), Some(10));
+        assert!((sequence.progress(minutes(30)) - 0.5).abs() < f32::EPSILON);
+        assert!(sequence.frame(minutes(69)).is_some());
+        assert!(sequence.frame(minutes(70)).is_none());
+    }
+
+    #[test]
+    fn light_sequence_snooze() {
+        let mut sequence = AlarmSequence::new(BrightnessCurve::Linear, None, None);
+        sequence.started -= minutes(30);
+        assert!(sequence.snooze_remaining().is_none());
+
+        sequence.snooze(minutes(10));
+        let remaining = sequence.snooze_remaining().unwrap();
+        assert!(remaining <= minutes(10) && remaining > minutes(9));
+
+        // Elapsed time is frozen whilst snoozed
+        let snoozed_at = sequence.snooze.unwrap().0;
+        sequence.snooze = Some((snoozed_at - minutes(5), Instant::now() + minutes(5)));
+        sequence.started -= minutes(5);
+        assert!(sequence.elapsed() >= minutes(30) && sequence.elapsed() < minutes(31));
+
+        sequence.resume();
+        assert!(sequence.snooze_remaining().is_none());
+        assert!(sequence.elapsed() >= minutes(30) && sequence.elapsed() < minutes(31));
+    }
+
+    #[test]
+    fn light_sequence_snooze_extend() {
+        let mut sequence = AlarmSequence::new(BrightnessCurve::Linear, None, None);
+        sequence.snooze(minutes(1));
+        let snoozed_at = sequence.snooze.unwrap().0;
+        sequence.snooze(minutes(10));
+        assert_eq!(sequence.snooze.unwrap().0, snoozed_at);
+        assert!(sequence.snooze_remaining().unwrap() > minutes(9));
+    }
+
+    #[test]
+    fn light_sequence_profile() {
+        let profile = serde_json::from_str::<Animation>(
+            r#"[{"offset_ms":0,"pixels":[[255,0,0]],"brightness":10},{"offset_ms":1000,"pixels":[[255,255,255],[255,255,255]],"brightness":100}]"#,
+        )
+        .unwrap();
+        let sequence =
+            AlarmSequence::new(BrightnessCurve::Linear, Some(60), None).with_profile(Some(profile));
+
+        let frame = sequence.frame(Duration::ZERO).unwrap();
+        assert!((frame.brightness - 0.1).abs() < f32::EPSILON);
+        assert_eq!(frame.colours, (255, 0, 0));
+        let pixels = frame.pixels.unwrap();
+        assert_eq!(pixels.len(), blinkt::NUM_PIXELS);
+        assert_eq!(pixels[1], (0, 0, 0));
+
+        // The animation is stretched to fit the ramp
+        let frame = sequence.frame(minutes(30)).unwrap();
+        assert!((frame.brightness - 0.55).abs() < 0.01);
+        assert_eq!(frame.pixels.unwrap()[1], (128, 128, 128));
+
+        // Scaled to the length of the strip
+        let frame = sequence
+            .clone()
+            .with_num_pixels(30)
+            .frame(Duration::ZERO)
+            .unwrap();
+        assert_eq!(frame.pixels.unwrap().len(), 30);
+
+        // Held at the last keyframe
+        let frame = sequence.frame(minutes(90)).unwrap();
+        assert!((frame.brightness - 1.0).abs() < f32::EPSILON);
+        assert_eq!(frame.colours, (255, 255, 255));
+        assert!(sequence.frame(minutes(105)).is_none());
+    }
+
+    #[test]
+    fn light_sequence_escalation() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(10), Some(5))
+            .with_escalation(Some(Escalation::Flash), Some(2));
+        assert!(!sequence.escalating(minutes(14)));
+        let frame = sequence.frame(minutes(14)).unwrap();
+        assert!((frame.brightness - 1.0).abs() < f32::EPSILON);
+
+        assert!(sequence.escalating(minutes(15)));
+        let frame = sequence.frame(minutes(15)).unwrap();
+        assert!((frame.brightness - 1.0).abs() < f32::EPSILON);
+        assert_eq!(frame.colours, gradient::SUNRISE[4].colour);
+        let frame = sequence
+            .frame(minutes(15) + Duration::from_millis(500))
+            .unwrap();
+        assert!(frame.brightness.abs() < f32::EPSILON);
+
+        // Times out once the maximum has passed
+        assert!(sequence.frame(minutes(16)).is_some());
+        assert!(!sequence.escalating(minutes(17)));
+        assert!(sequence.frame(minutes(17)).is_none());
+
+        // Default maximum
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(10), Some(0))
+            .with_escalation(Some(Escalation::Pulse), None);
+        assert!(sequence.frame(minutes(24)).is_some());
+        assert!(sequence.frame(minutes(25)).is_none());
+
+        // Without an escalation
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, Some(10), Some(0))
+            .with_escalation(None, Some(30));
+        assert!(!sequence.escalating(minutes(10)));
+        assert!(sequence.frame(minutes(10)).is_none());
+    }
+
+    #[test]
+    fn light_sequence_with_elapsed() {
+        let sequence =
+            AlarmSequence::new(BrightnessCurve::Linear, None, None).with_elapsed(minutes(45));
+        let elapsed = sequence.elapsed();
+        assert!(elapsed >= minutes(45) && elapsed < minutes(46));
+        assert_eq!(sequence.step(elapsed), 6);
+        assert!(!sequence.finished(elapsed));
+
+        let sequence =
+            AlarmSequence::new(BrightnessCurve::Linear, None, None).with_elapsed(minutes(135));
+        assert!(sequence.finished(sequence.elapsed()));
+    }
+
+    #[test]
+    fn light_sequence_frame_finished() {
+        let sequence = AlarmSequence::new(BrightnessCurve::Linear, None, None);
+        assert!(sequence.frame(minutes(135)).is_none());
+        assert!(sequence.frame(minutes(200)).is_none());
+    }
+}
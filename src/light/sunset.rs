@@ -0,0 +1,106 @@
+use std::time::{Duration, Instant};
+
+use super::{DEFAULT_COLOUR, Rgb, sequence::Frame};
+
+/// Default minutes taken to fade from the starting brightness to off
+pub const SUNSET_MINUTES: u16 = 30;
+
+/// Default starting brightness percentage
+const SUNSET_BRIGHTNESS: u8 = 50;
+
+/// An in-progress wind-down sequence, the reverse of an alarm, fading from a starting brightness to off
+#[derive(Debug, Clone, Copy)]
+pub struct SunsetSequence {
+    brightness: f32,
+    colour: Rgb,
+    duration: Duration,
+    started: Instant,
+}
+
+impl SunsetSequence {
+    /// Create a sequence starting now, `None` values use the defaults
+    pub fn new(brightness: Option<u8>, colour: Option<Rgb>, minutes: Option<u16>) -> Self {
+        Self {
+            brightness: f32::from(brightness.unwrap_or(SUNSET_BRIGHTNESS).min(100)) / 100.0,
+            colour: colour.unwrap_or(DEFAULT_COLOUR),
+            duration: Duration::from_secs(u64::from(minutes.unwrap_or(SUNSET_MINUTES)) * 60),
+            started: Instant::now(),
+        }
+    }
+
+    /// Time since the sequence was started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Time left until the light is turned off
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    /// Compute the frame for a given elapsed time, `None` once the fade has finished
+    pub fn frame(&self, elapsed: Duration) -> Option<Frame> {
+        if elapsed >= self.duration {
+            return None;
+        }
+        let progress = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        Some(Frame {
+            brightness: self.brightness * (1.0 - progress),
+            colours: self.colour,
+            pixels: None,
+        })
+    }
+}
+
+/// SunsetSequence tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_sunset -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const fn minutes(x: u64) -> Duration {
+        Duration::from_secs(x * 60)
+    }
+
+    #[test]
+    fn light_sunset_defaults() {
+        let sequence = SunsetSequence::new(None, None, None);
+        let frame = sequence.frame(Duration::ZERO).unwrap();
+        assert!((frame.brightness - 0.5).abs() < f32::EPSILON);
+        assert_eq!(frame.colours, DEFAULT_COLOUR);
+        assert!(sequence.frame(minutes(29)).is_some());
+        assert!(sequence.frame(minutes(30)).is_none());
+    }
+
+    #[test]
+    fn light_sunset_fade() {
+        let sequence = SunsetSequence::new(Some(80), Some((255, 0, 0)), Some(10));
+        let start = sequence.frame(Duration::ZERO).unwrap();
+        assert!((start.brightness - 0.8).abs() < f32::EPSILON);
+        assert_eq!(start.colours, (255, 0, 0));
+
+        let half = sequence.frame(minutes(5)).unwrap();
+        assert!((half.brightness - 0.4).abs() < f32::EPSILON);
+
+        let mut previous = start.brightness;
+        for second in 1..600 {
+            let frame = sequence.frame(Duration::from_secs(second)).unwrap();
+            assert!(frame.brightness < previous);
+            previous = frame.brightness;
+        }
+        assert!(sequence.frame(minutes(10)).is_none());
+    }
+
+    #[test]
+    fn light_sunset_remaining() {
+        let mut sequence = SunsetSequence::new(None, None, Some(10));
+        assert!(sequence.remaining() > minutes(9));
+        sequence.started -= minutes(4);
+        let remaining = sequence.remaining();
+        assert!(remaining <= minutes(6) && remaining > minutes(5));
+        sequence.started -= minutes(20);
+        assert_eq!(sequence.remaining(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,240 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::blinkt::{self, Blinkt};
+
+/// How long to wait between attempts to initialise a missing blinkt
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Consecutive failed writes before the blinkt is treated as missing, and initialised again
+const MAX_FAILURES: u32 = 5;
+
+/// Whether the LED hardware is working
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LedHealth {
+    /// The last frame was written
+    Ok,
+    /// Found, but recent frames have failed to write
+    Degraded,
+    /// Not found, initialisation is retried periodically
+    #[default]
+    Missing,
+}
+
+pub type Connect = Box<dyn FnMut() -> blinkt::Result<Blinkt> + Send>;
+
+/// Connect to an already created blinkt, any later attempts fail
+#[cfg(test)]
+pub fn connect_once(blinkt: Blinkt) -> Connect {
+    let mut blinkt = Some(blinkt);
+    Box::new(move || {
+        blinkt
+            .take()
+            .ok_or_else(|| blinkt::Error::Io(std::io::Error::other("already connected")))
+    })
+}
+
+/// Owns the blinkt, retrying initialisation whilst it is missing, and tracking consecutive write failures
+pub struct Supervisor {
+    blinkt: Option<Blinkt>,
+    connect: Connect,
+    failures: u32,
+    retry_at: Instant,
+}
+
+impl Supervisor {
+    /// Create the supervisor, and attempt the first initialisation straight away
+    pub fn new(connect: Connect) -> Self {
+        let mut supervisor = Self {
+            blinkt: None,
+            connect,
+            failures: 0,
+            retry_at: Instant::now(),
+        };
+        supervisor.try_connect();
+        supervisor
+    }
+
+    pub fn health(&self) -> LedHealth {
+        if self.blinkt.is_none() {
+            LedHealth::Missing
+        } else if self.failures > 0 {
+            LedHealth::Degraded
+        } else {
+            LedHealth::Ok
+        }
+    }
+
+    /// Attempt to initialise the blinkt, if missing and the retry interval has passed, returns true if it is newly connected
+    pub fn try_connect(&mut self) -> bool {
+        if self.blinkt.is_some() || Instant::now() < self.retry_at {
+            return false;
+        }
+        match (self.connect)() {
+            Ok(blinkt) => {
+                tracing::info!("Blinkt connected");
+                self.blinkt = Some(blinkt);
+                self.failures = 0;
+                true
+            }
+            Err(e) => {
+                tracing::error!("No Blinkt found: {e}");
+                self.retry_at = Instant::now() + RETRY_INTERVAL;
+                false
+            }
+        }
+    }
+
+    /// The blinkt, `None` whilst missing
+    pub const fn blinkt(&mut self) -> Option<&mut Blinkt> {
+        self.blinkt.as_mut()
+    }
+
+    /// Record the result of a write, too many consecutive failures and the blinkt is dropped, to be initialised again
+    pub fn record(&mut self, ok: bool) {
+        if ok {
+            self.failures = 0;
+            return;
+        }
+        self.failures += 1;
+        if self.failures >= MAX_FAILURES {
+            tracing::error!(
+                "{MAX_FAILURES} consecutive failed writes, Blinkt will be initialised again"
+            );
+            if let Some(mut blinkt) = self.blinkt.take() {
+                blinkt.set_clear_on_drop(false);
+            }
+            self.failures = 0;
+            self.retry_at = Instant::now() + RETRY_INTERVAL;
+        }
+    }
+
+    /// Time until the next initialisation attempt, `None` when the blinkt isn't missing
+    pub fn retry_in(&self) -> Option<Duration> {
+        self.blinkt
+            .is_none()
+            .then(|| self.retry_at.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// Supervisor tests
+///
+/// cargo watch -q -c -w src/ -x 'test light_supervisor -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used)]
+mod tests {
+    use std::{
+        io,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, AtomicU32, Ordering},
+        },
+    };
+
+    use super::*;
+    use crate::blinkt::SerialOutput;
+
+    /// Output that fails to write whilst the flag is set
+    struct FailingOutput(Arc<AtomicBool>);
+
+    impl SerialOutput for FailingOutput {
+        fn write(&mut self, _: &[u8]) -> blinkt::Result<()> {
+            if self.0.load(Ordering::SeqCst) {
+                return Err(blinkt::Error::Io(io::Error::other("write failed")));
+            }
+            Ok(())
+        }
+    }
+
+    /// Connect that fails until `available` is set, counting attempts
+    fn connect(
+        available: &Arc<AtomicBool>,
+        failing: &Arc<AtomicBool>,
+        attempts: &Arc<AtomicU32>,
+    ) -> Connect {
+        let available = Arc::clone(available);
+        let failing = Arc::clone(failing);
+        let attempts = Arc::clone(attempts);
+        Box::new(move || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            if available.load(Ordering::SeqCst) {
+                let mut blinkt = Blinkt::with_output(FailingOutput(Arc::clone(&failing)), 1);
+                blinkt.set_clear_on_drop(false);
+                Ok(blinkt)
+            } else {
+                Err(blinkt::Error::Io(io::Error::other("not found")))
+            }
+        })
+    }
+
+    #[test]
+    fn light_supervisor_missing() {
+        let available = Arc::new(AtomicBool::new(false));
+        let failing = Arc::new(AtomicBool::new(false));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let mut supervisor = Supervisor::new(connect(&available, &failing, &attempts));
+
+        assert_eq!(supervisor.health(), LedHealth::Missing);
+        assert!(supervisor.blinkt().is_none());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert!(supervisor.retry_in().unwrap() > Duration::from_secs(29));
+
+        // Not retried until the interval has passed
+        available.store(true, Ordering::SeqCst);
+        assert!(!supervisor.try_connect());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        supervisor.retry_at = Instant::now();
+        assert!(supervisor.try_connect());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(supervisor.health(), LedHealth::Ok);
+        assert!(supervisor.retry_in().is_none());
+
+        // Already connected
+        assert!(!supervisor.try_connect());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn light_supervisor_failures() {
+        let available = Arc::new(AtomicBool::new(true));
+        let failing = Arc::new(AtomicBool::new(false));
+        let attempts = Arc::new(AtomicU32::new(0));
+        let mut supervisor = Supervisor::new(connect(&available, &failing, &attempts));
+        assert_eq!(supervisor.health(), LedHealth::Ok);
+
+        let show = |supervisor: &mut Supervisor| {
+            let ok = supervisor.blinkt().unwrap().force_show().is_ok();
+            supervisor.record(ok);
+        };
+
+        failing.store(true, Ordering::SeqCst);
+        show(&mut supervisor);
+        assert_eq!(supervisor.health(), LedHealth::Degraded);
+
+        // A successful write recovers
+        failing.store(false, Ordering::SeqCst);
+        show(&mut supervisor);
+        assert_eq!(supervisor.health(), LedHealth::Ok);
+
+        // Too many consecutive failures, and the blinkt is treated as missing
+        failing.store(true, Ordering::SeqCst);
+        for _ in 0..MAX_FAILURES - 1 {
+            show(&mut supervisor);
+            assert_eq!(supervisor.health(), LedHealth::Degraded);
+        }
+        show(&mut supervisor);
+        assert_eq!(supervisor.health(), LedHealth::Missing);
+        assert!(supervisor.retry_in().is_some());
+
+        failing.store(false, Ordering::SeqCst);
+        supervisor.retry_at = Instant::now();
+        assert!(supervisor.try_connect());
+        assert_eq!(supervisor.health(), LedHealth::Ok);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
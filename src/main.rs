@@ -69,7 +69,11 @@ mod tests {
     use sqlx::SqlitePool;
     use uuid::Uuid;
 
-    use crate::{S, app_env::AppEnv, db::init_db};
+    use crate::{
+        S,
+        app_env::{AppEnv, LedOutput, LedStrip},
+        db::init_db,
+    };
     /// Close database connection, and delete all test files
     pub async fn test_cleanup(uuid: Uuid, db: Option<SqlitePool>) {
         if let Some(db) = db {
@@ -99,6 +103,8 @@ mod tests {
             ws_apikey: S!("ws_apikey"),
             ws_password: S!("ws_password"),
             ws_token_address: S!("ws_token_address"),
+            led_output: LedOutput::Recording,
+            led_strip: LedStrip::default(),
         }
     }
 
@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use async_channel::{Receiver, Sender};
 use sqlx::SqlitePool;
@@ -9,7 +12,12 @@ use crate::{
     alarm_schedule::AlarmSchedule,
     app_env::AppEnv,
     app_error::AppError,
-    light::{LightControl, LightMsg},
+    blinkt::CalibrationSettings,
+    db::{AlarmOutcome, ModelActiveAlarm, ModelAlarmOutcome, ModelCalibration},
+    light::{
+        AlarmSequence, Animation, EffectSettings, LedHealth, LedStatus, LightControl,
+        LightDuration, LightMsg, LightSettings, Rgb, SunsetSequence,
+    },
     ws::{self, ConnectionDetails, Socket, WSSender, open_connection},
     ws_messages::Response,
 };
@@ -69,14 +77,28 @@ impl StatusFile {
 }
 #[derive(Debug)]
 pub enum Msg {
+    AlarmOutcome(Option<i64>, AlarmOutcome),
+    /// Alarm id, time since the sequence started, and the time left of any snooze
+    AlarmProgress(i64, Duration, Option<Duration>),
+    Calibration(CalibrationSettings),
+    CalibrationPreview(CalibrationSettings, Option<Rgb>),
+    CalibrationPreviewEnd,
+    DismissAlarm,
+    Effect(Option<EffectSettings>),
     Exit,
-    GetLEDStatus(Sender<bool>),
+    GetLEDStatus(Sender<LedStatus>),
+    LedHealth(LedHealth),
+    NightLight(LightDuration),
     Ping,
+    PlayAnimation(i64, Animation, bool),
     Received(String),
     ResetAlarmLoop,
+    Scene(i64, LightSettings),
     SendLEDStatus,
-    SetLED(bool),
-    StartAlarm,
+    SetLED(Option<LightSettings>),
+    Snooze(Option<u16>),
+    StartAlarm(AlarmSequence),
+    StartSunset(SunsetSequence),
     StatusFile(Option<()>),
     ToSend((Response, Option<bool>)),
     WsClose,
@@ -122,11 +144,65 @@ impl MessageHandler {
         )
         .0?;
 
-        // Turn the light off at start
-        self.light_tx.send(LightMsg::Off).await.ok();
+        let calibration = ModelCalibration::get(&self.sqlite)
+            .await
+            .unwrap_or_default()
+            .settings();
+        self.light_tx
+            .send(LightMsg::Calibration(calibration))
+            .await
+            .ok();
+
+        // Resume an alarm that was in progress when the app was stopped, otherwise turn the light off at start
+        match AlarmSchedule::resume(&self.sqlite).await {
+            Some(sequence) => self.light_tx.send(LightMsg::Alarm(sequence)).await.ok(),
+            None => self.light_tx.send(LightMsg::Off).await.ok(),
+        };
 
         while let Ok(msg) = self.rx.recv().await {
             match msg {
+                Msg::AlarmOutcome(alarm_id, outcome) => {
+                    let (added, _) = tokio::join!(
+                        ModelAlarmOutcome::add(&self.sqlite, alarm_id, outcome),
+                        ModelActiveAlarm::delete(&self.sqlite)
+                    );
+                    if let Err(e) = added {
+                        tracing::error!("{e}");
+                    }
+                    self.send_status();
+                }
+                Msg::AlarmProgress(alarm_id, elapsed, snooze) => {
+                    if let Err(e) =
+                        ModelActiveAlarm::set(&self.sqlite, alarm_id, elapsed, snooze).await
+                    {
+                        tracing::error!("{e}");
+                    }
+                }
+                Msg::Calibration(settings) => {
+                    self.light_tx
+                        .send(LightMsg::Calibration(settings))
+                        .await
+                        .ok();
+                }
+                Msg::CalibrationPreview(settings, colour) => {
+                    self.light_tx
+                        .send(LightMsg::CalibrationPreview(settings, colour))
+                        .await
+                        .ok();
+                }
+                Msg::CalibrationPreviewEnd => {
+                    self.light_tx
+                        .send(LightMsg::CalibrationPreviewEnd)
+                        .await
+                        .ok();
+                }
+                Msg::DismissAlarm => {
+                    self.light_tx.send(LightMsg::Dismiss).await.ok();
+                }
+                Msg::Effect(settings) => {
+                    let msg = settings.map_or(LightMsg::EffectStop, LightMsg::Effect);
+                    self.light_tx.send(msg).await.ok();
+                }
                 Msg::Exit => {
                     self.light_tx.send(LightMsg::Exit).await.ok();
                     if let Some(socket) = &mut self.socket {
@@ -136,12 +212,32 @@ impl MessageHandler {
                 Msg::GetLEDStatus(sender) => {
                     self.light_tx.send(LightMsg::Get(sender)).await.ok();
                 }
+                Msg::LedHealth(health) => {
+                    if health == LedHealth::Ok {
+                        tracing::info!("LED health: {health:?}");
+                    } else {
+                        tracing::warn!("LED health: {health:?}");
+                    }
+                    self.send_status();
+                }
+                Msg::NightLight(duration) => {
+                    self.light_tx
+                        .send(LightMsg::NightLight(duration))
+                        .await
+                        .ok();
+                }
                 Msg::StatusFile(create) => self.status_file.toggle(create).await,
                 Msg::Ping => {
                     if let Some(socket) = &mut self.socket {
                         socket.on_ping(&self.tx);
                     }
                 }
+                Msg::PlayAnimation(animation_id, animation, looping) => {
+                    self.light_tx
+                        .send(LightMsg::Animation(animation_id, animation, looping))
+                        .await
+                        .ok();
+                }
                 Msg::Received(msg) => {
                     let ws_sender = self.ws_sender.clone();
                     tokio::spawn(async move {
@@ -153,12 +249,25 @@ impl MessageHandler {
                     self.send_status();
                 }
 
+                Msg::Scene(scene_id, settings) => {
+                    self.light_tx
+                        .send(LightMsg::Scene(scene_id, settings))
+                        .await
+                        .ok();
+                }
                 Msg::SendLEDStatus => self.send_led_status(),
-                Msg::SetLED(status) => {
-                    self.light_tx.send(LightMsg::Toggle(status)).await.ok();
+                Msg::SetLED(settings) => {
+                    let msg = settings.map_or(LightMsg::Off, LightMsg::On);
+                    self.light_tx.send(msg).await.ok();
+                }
+                Msg::Snooze(minutes) => {
+                    self.light_tx.send(LightMsg::Snooze(minutes)).await.ok();
+                }
+                Msg::StartAlarm(sequence) => {
+                    self.light_tx.send(LightMsg::Alarm(sequence)).await.ok();
                 }
-                Msg::StartAlarm => {
-                    self.light_tx.send(LightMsg::Alarm).await.ok();
+                Msg::StartSunset(sequence) => {
+                    self.light_tx.send(LightMsg::Sunset(sequence)).await.ok();
                 }
                 Msg::ToSend((response, cache)) => {
                     if let Some(socket) = &mut self.socket {
@@ -186,12 +295,13 @@ impl MessageHandler {
         let ws_sender = ws::WSSender::new(&app_env, &sqlite, &tx);
         let alarm_schedule = AlarmSchedule::new(&tx);
         let status_file = StatusFile::new(&app_env);
+        let light_tx = LightControl::init(&tx, &app_env);
 
         Self {
             alarm_schedule,
             app_env,
             connection_details: ConnectionDetails::new(),
-            light_tx: LightControl::init(&tx),
+            light_tx,
             rx,
             socket: None,
             status_file,
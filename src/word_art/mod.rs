@@ -7,6 +7,7 @@ use figlet_rs::FIGlet;
 use font::{Color, FontName};
 
 const RESET: &str = "\x1b[0m";
+const PIXEL: &str = "\u{2588}\u{2588}";
 
 /// Convert input string to ASCII art
 fn create_art(input: &str, fontname: FontName) -> String {
@@ -26,6 +27,17 @@ fn paint_text(text: &str, color: Color) -> String {
     format!("{painted}{RESET}")
 }
 
+/// Paint a row of solid blocks, one per colour, using 24-bit ANSI colours
+pub fn paint_pixels(colours: &[(u8, u8, u8)]) -> String {
+    let painted = colours
+        .iter()
+        .fold(String::new(), |mut output, (red, green, blue)| {
+            write!(output, "\x1b[38;2;{red};{green};{blue}m{PIXEL}").ok();
+            output
+        });
+    format!("{painted}{RESET}")
+}
+
 /// Show the intro texts
 fn display_intro(app_envs: &AppEnv) -> String {
     let beluga = paint_text(
@@ -105,6 +117,16 @@ mod tests {
         assert!(result.contains("!! DEBUG"));
     }
 
+    #[test]
+    fn word_art_paint_pixels() {
+        let result = paint_pixels(&[(255, 0, 0), (0, 128, 255)]);
+        assert_eq!(
+            result,
+            "\x1b[38;2;255;0;0m\u{2588}\u{2588}\x1b[38;2;0;128;255m\u{2588}\u{2588}\x1b[0m"
+        );
+        assert_eq!(paint_pixels(&[]), RESET);
+    }
+
     #[test]
     fn word_art_display_intro() {
         let app_envs = gen_app_envs(uuid::Uuid::new_v4());
@@ -5,13 +5,27 @@ use std::{process, time::Instant};
 use crate::C;
 use crate::message_handler::Msg;
 use crate::sysinfo::SysInfo;
-use crate::ws_messages::{MessageValues, ParsedMessage, PiStatus, Response};
+use crate::ws_messages::{
+    AddAlarm, AddAnimation, AddScene, AddSunset, Calibration, CalibrationPreview, Effect, Light,
+    MessageValues, NightLightWindow, ParsedMessage, PiStatus, PlayAnimation, Response,
+    StoredStatus, Sunset,
+};
 use crate::{
     app_env::AppEnv,
-    db::{ModelAlarm, ModelTimezone},
+    blinkt::CalibrationSettings,
+    db::{
+        AlarmOptions, ModelAlarm, ModelAlarmOutcome, ModelAnimation, ModelCalibration, ModelCurve,
+        ModelScene, ModelSetting, ModelSunset, ModelTimezone, NightLightSetting,
+    },
+    light::{
+        BrightnessCurve, EffectSettings, LedStatus, LightDuration, LightSettings, SunsetSequence,
+    },
     ws_messages::to_struct,
 };
 
+/// Number of recent alarm outcomes included in the pi status
+const ALARM_OUTCOMES: i64 = 10;
+
 #[derive(Debug, Clone)]
 pub struct WSSender {
     app_envs: AppEnv,
@@ -43,30 +57,68 @@ impl WSSender {
                 MessageValues::Valid(data) => match data {
                     ParsedMessage::DeleteAll => self.delete_all().await,
                     ParsedMessage::DeleteOne(id) => self.delete_one(id.alarm_id).await,
+                    ParsedMessage::DeleteSunset(id) => self.delete_sunset(id.sunset_id).await,
+                    ParsedMessage::DismissAlarm => self.dismiss_alarm().await,
+                    ParsedMessage::Effect(data) => self.effect(Some(data)).await,
+                    ParsedMessage::EffectStop => self.effect(None).await,
                     ParsedMessage::LedStatus => self.send_led_status().await,
                     ParsedMessage::Restart => self.restart().await,
                     ParsedMessage::TimeZone(timezone) => self.time_zone(timezone.zone).await,
-                    ParsedMessage::AddAlarm(data) => {
-                        self.add_alarm(data.days, data.hour, data.minute).await;
+                    ParsedMessage::AddAlarm(data) => self.add_alarm(data).await,
+                    ParsedMessage::AddAnimation(data) => self.add_animation(data).await,
+                    ParsedMessage::AddScene(data) => self.add_scene(data).await,
+                    ParsedMessage::AddSunset(data) => self.add_sunset(data).await,
+                    ParsedMessage::Animations => self.send_animations().await,
+                    ParsedMessage::ApplyScene(id) => self.apply_scene(id.scene_id).await,
+                    ParsedMessage::Calibration(data) => self.calibration(data).await,
+                    ParsedMessage::CalibrationPreview(data) => {
+                        self.calibration_preview(data).await;
+                    }
+                    ParsedMessage::CalibrationPreviewEnd => {
+                        self.tx.send(Msg::CalibrationPreviewEnd).await.ok();
+                    }
+                    ParsedMessage::DeleteAnimation(id) => {
+                        self.delete_animation(id.animation_id).await;
+                    }
+                    ParsedMessage::DeleteScene(id) => self.delete_scene(id.scene_id).await,
+                    ParsedMessage::PlayAnimation(data) => self.play_animation(data).await,
+                    ParsedMessage::Scenes => self.send_scenes().await,
+                    ParsedMessage::Light(data) => self.toggle_light(data).await,
+                    ParsedMessage::LightDuration { duration } => {
+                        self.light_duration(duration).await;
                     }
-                    ParsedMessage::Light { status } => self.toggle_light(status).await,
+                    ParsedMessage::NightLight { duration } => self.night_light(duration).await,
+                    ParsedMessage::NightLightWindow(data) => self.night_light_window(data).await,
+                    ParsedMessage::Snooze { minutes } => self.snooze(minutes).await,
                     ParsedMessage::Status => self.send_status().await,
+                    ParsedMessage::Sunset(data) => self.sunset(data).await,
+                    ParsedMessage::SunriseCurve { curve } => self.sunrise_curve(curve).await,
                 },
             }
         }
     }
 
-    /// Get the current value of the light bool
-    async fn get_light_value(&self) -> bool {
+    /// Get the current status of the light
+    async fn get_light_value(&self) -> LedStatus {
         let (t, r) = async_channel::bounded(1);
         self.tx.send(Msg::GetLEDStatus(t)).await.ok();
         r.recv().await.unwrap_or_default()
     }
 
     /// Add a new alarm to database, and update alarm_schedule alarm vector
-    async fn add_alarm(&self, day: Vec<u8>, hour: u8, minute: u8) {
-        for i in day {
-            if let Err(e) = ModelAlarm::add(&self.sqlite, (i, hour, minute)).await {
+    async fn add_alarm(&self, data: AddAlarm) {
+        let options = AlarmOptions {
+            ramp_minutes: data.ramp_minutes,
+            hold_minutes: data.hold_minutes,
+            mode: data.mode,
+            animation_id: data.animation_id,
+            escalation: data.escalation,
+            escalation_minutes: data.escalation_minutes,
+        };
+        for i in data.days {
+            if let Err(e) =
+                ModelAlarm::add(&self.sqlite, (i, data.hour, data.minute), &options).await
+            {
                 tracing::debug!("{e}");
             }
         }
@@ -74,9 +126,114 @@ impl WSSender {
         self.send_status().await;
     }
 
+    /// Save a new named scene to the database
+    async fn add_scene(&self, data: AddScene) {
+        let settings = LightSettings {
+            brightness: data.brightness,
+            colour: data.colour,
+            pixels: data.pixels,
+            duration: data.duration,
+        };
+        if let Err(e) = ModelScene::add(&self.sqlite, &data.name, &settings).await {
+            tracing::debug!("{e}");
+        }
+        self.send_status().await;
+    }
+
+    /// Turn the light on using a stored scene
+    async fn apply_scene(&self, id: i64) {
+        match ModelScene::get(&self.sqlite, id).await {
+            Ok(Some(scene)) => {
+                self.tx
+                    .send(Msg::Scene(scene.scene_id, scene.settings()))
+                    .await
+                    .ok();
+            }
+            Ok(None) => tracing::debug!("unknown scene: {id}"),
+            Err(e) => tracing::error!("{e}"),
+        }
+    }
+
+    /// Delete a stored scene, by id, a light currently showing the scene is left on
+    async fn delete_scene(&self, id: i64) {
+        ModelScene::delete(&self.sqlite, id).await.unwrap_or(());
+        self.send_status().await;
+    }
+
+    /// Send the list of stored scenes
+    async fn send_scenes(&self) {
+        let scenes = ModelScene::get_all(&self.sqlite).await.unwrap_or_default();
+        self.send_ws_response(Response::Scenes(scenes), None).await;
+    }
+
+    /// Send every stored animation, including the keyframes
+    async fn send_animations(&self) {
+        let animations = ModelAnimation::get_all(&self.sqlite)
+            .await
+            .unwrap_or_default();
+        self.send_ws_response(Response::Animations(animations), None)
+            .await;
+    }
+
+    /// Save a new named keyframe animation to the database
+    async fn add_animation(&self, data: AddAnimation) {
+        if let Err(e) = ModelAnimation::add(&self.sqlite, &data.name, &data.keyframes).await {
+            tracing::debug!("{e}");
+        }
+        self.send_status().await;
+    }
+
+    /// Delete a stored animation, by id, alarms using it as a sunrise profile revert to the default sunrise
+    async fn delete_animation(&self, id: i64) {
+        ModelAnimation::delete(&self.sqlite, id).await.unwrap_or(());
+        tokio::join!(self.update_loop(), self.send_status());
+    }
+
+    /// Play a stored animation, either once, or on repeat
+    async fn play_animation(&self, data: PlayAnimation) {
+        match ModelAnimation::get(&self.sqlite, data.animation_id).await {
+            Ok(Some(animation)) => {
+                self.tx
+                    .send(Msg::PlayAnimation(
+                        animation.animation_id,
+                        animation.animation,
+                        data.looping,
+                    ))
+                    .await
+                    .ok();
+            }
+            Ok(None) => tracing::debug!("unknown animation: {}", data.animation_id),
+            Err(e) => tracing::error!("{e}"),
+        }
+    }
+
+    /// Add a new recurring sunset to database, and update alarm_schedule sunset vector
+    async fn add_sunset(&self, data: AddSunset) {
+        for i in data.days {
+            if let Err(e) = ModelSunset::add(
+                &self.sqlite,
+                (i, data.hour, data.minute),
+                data.brightness,
+                data.colour,
+                data.minutes,
+            )
+            .await
+            {
+                tracing::debug!("{e}");
+            }
+        }
+        self.update_loop().await;
+        self.send_status().await;
+    }
+
+    /// Delete from database a given recurring sunset, by id, and also remove from alarm_schedule sunset vector
+    async fn delete_sunset(&self, id: i64) {
+        ModelSunset::delete(&self.sqlite, id).await.unwrap_or(());
+        tokio::join!(self.update_loop(), self.send_status());
+    }
+
     /// Delete all alarms in database, and update alarm_schedule alarm vector
-    /// If the alarm sequence has started, and you delete all alarms, the light is still on
-    /// Would need to set the light status to false, but that could also set the light off if on not during an alarm sequence
+    /// If the alarm sequence has started, and you delete all alarms, the light is still on, use dismiss_alarm to end it
     async fn delete_all(&self) {
         ModelAlarm::delete_all(&self.sqlite).await.ok();
         tokio::join!(self.update_loop(), self.send_status());
@@ -89,15 +246,10 @@ impl WSSender {
     }
 
     /// This also needs to be send from alarm sequencer
-    /// return true if led light is currently turned on
+    /// return the led light status, and remaining snooze time
     pub async fn send_led_status(&self) {
-        self.send_ws_response(
-            Response::LedStatus {
-                status: self.get_light_value().await,
-            },
-            None,
-        )
-        .await;
+        self.send_ws_response(Response::LedStatus(self.get_light_value().await), None)
+            .await;
     }
 
     /// Force quite program, assumes running in an auto-restart container, or systemd, in order to start again immediately
@@ -122,9 +274,132 @@ impl WSSender {
         }
     }
 
-    /// turn light either on or off
-    async fn toggle_light(&self, status: bool) {
-        self.tx.send(Msg::SetLED(status)).await.ok();
+    /// Save a new colour calibration, and apply it to the light
+    async fn calibration(&self, data: Calibration) {
+        let settings = CalibrationSettings {
+            gamma: data.gamma,
+            white_point: data.white_point,
+        };
+        match ModelCalibration::update(&self.sqlite, &settings).await {
+            Err(e) => tracing::error!("{e}"),
+            Ok(_) => {
+                self.tx.send(Msg::Calibration(settings)).await.ok();
+            }
+        }
+        self.send_status().await;
+    }
+
+    /// Apply a colour calibration to the light, without saving it
+    async fn calibration_preview(&self, data: CalibrationPreview) {
+        let settings = CalibrationSettings {
+            gamma: data.gamma,
+            white_point: data.white_point,
+        };
+        self.tx
+            .send(Msg::CalibrationPreview(settings, data.colour))
+            .await
+            .ok();
+    }
+
+    /// Change the brightness curve used by the alarm sequence, and update the alarm scheduler
+    async fn sunrise_curve(&self, curve: BrightnessCurve) {
+        match ModelCurve::update(&self.sqlite, curve).await {
+            Err(e) => tracing::error!("{e}"),
+            Ok(_) => self.update_loop().await,
+        }
+        self.send_status().await;
+    }
+
+    /// Change the default length of time the light stays on for, when turned on manually
+    async fn light_duration(&self, duration: LightDuration) {
+        if let Err(e) = ModelSetting::update_light_duration(&self.sqlite, duration).await {
+            tracing::error!("{e}");
+        }
+        self.send_status().await;
+    }
+
+    /// Change the hours during which a manual "on" becomes a night light, and how long the night light stays on for
+    async fn night_light_window(&self, data: NightLightWindow) {
+        let night_light = NightLightSetting {
+            start: data.start,
+            end: data.end,
+            duration: data.duration,
+        };
+        if let Err(e) = ModelSetting::update_night_light(&self.sqlite, &night_light).await {
+            tracing::error!("{e}");
+        }
+        self.send_status().await;
+    }
+
+    /// Turn on the night light, without a duration, the default from the database is used
+    async fn night_light(&self, duration: Option<LightDuration>) {
+        let duration = match duration {
+            Some(duration) => duration,
+            None => {
+                ModelSetting::get(&self.sqlite)
+                    .await
+                    .unwrap_or_default()
+                    .night_light()
+                    .duration
+            }
+        };
+        self.tx.send(Msg::NightLight(duration)).await.ok();
+    }
+
+    /// End an in progress alarm sequence, without affecting the light if it was turned on manually
+    async fn dismiss_alarm(&self) {
+        self.tx.send(Msg::DismissAlarm).await.ok();
+    }
+
+    /// Start an effect, or stop the current effect when `None`
+    async fn effect(&self, data: Option<Effect>) {
+        let settings = data.map(|data| EffectSettings {
+            name: data.effect,
+            colour: data.colour,
+            brightness: data.brightness,
+            period_ms: data.period_ms,
+        });
+        self.tx.send(Msg::Effect(settings)).await.ok();
+    }
+
+    /// Start fading the light to off
+    async fn sunset(&self, data: Sunset) {
+        let sequence = SunsetSequence::new(data.brightness, data.colour, data.minutes);
+        self.tx.send(Msg::StartSunset(sequence)).await.ok();
+    }
+
+    /// Snooze an in progress alarm
+    async fn snooze(&self, minutes: Option<u16>) {
+        self.tx.send(Msg::Snooze(minutes)).await.ok();
+    }
+
+    /// turn light either on, with optional colour, brightness, and duration, or off
+    /// Without a duration, the default from the database is used.
+    /// A plain "on", without a colour, brightness, or pixels, during the night light window turns on the night light instead
+    async fn toggle_light(&self, data: Light) {
+        let settings = if data.status {
+            let (setting, time_zone) = tokio::join!(
+                ModelSetting::get(&self.sqlite),
+                ModelTimezone::get(&self.sqlite)
+            );
+            let setting = setting.unwrap_or_default();
+            let plain = data.colour.is_none() && data.brightness.is_none() && data.pixels.is_none();
+            if plain && setting.is_night(time_zone.unwrap_or_default().to_time().hour()) {
+                let duration = data.duration.unwrap_or(setting.night_light().duration);
+                self.tx.send(Msg::NightLight(duration)).await.ok();
+                return;
+            }
+            let duration = data.duration.unwrap_or(setting.light_duration());
+            Some(LightSettings {
+                brightness: data.brightness,
+                colour: data.colour,
+                pixels: data.pixels,
+                duration: Some(duration),
+            })
+        } else {
+            None
+        };
+        self.tx.send(Msg::SetLED(settings)).await.ok();
     }
 
     /// Send a message to restar the alarm loop, used when alarms added or deleted
@@ -146,13 +421,43 @@ impl WSSender {
 
     /// Generate, and send, pi information
     pub async fn send_status(&self) {
-        let (info, alarms) = tokio::join!(
+        let (
+            info,
+            alarms,
+            alarm_outcomes,
+            animations,
+            calibration,
+            sunsets,
+            scenes,
+            curve,
+            setting,
+            led_status,
+        ) = tokio::join!(
             SysInfo::new(&self.sqlite, &self.app_envs),
-            ModelAlarm::get_all(&self.sqlite)
+            ModelAlarm::get_all(&self.sqlite),
+            ModelAlarmOutcome::get_recent(&self.sqlite, ALARM_OUTCOMES),
+            ModelAnimation::get_all(&self.sqlite),
+            ModelCalibration::get(&self.sqlite),
+            ModelSunset::get_all(&self.sqlite),
+            ModelScene::get_all(&self.sqlite),
+            ModelCurve::get(&self.sqlite),
+            ModelSetting::get(&self.sqlite),
+            self.get_light_value()
         );
+        let stored = StoredStatus {
+            alarms: alarms.unwrap_or_default(),
+            alarm_outcomes: alarm_outcomes.unwrap_or_default(),
+            animations: animations.unwrap_or_default(),
+            calibration: calibration.unwrap_or_default().settings(),
+            scenes: scenes.unwrap_or_default(),
+            setting: setting.unwrap_or_default(),
+            sunrise_curve: curve.unwrap_or_default().curve_name,
+            sunsets: sunsets.unwrap_or_default(),
+        };
         let info = PiStatus::new(
             info,
-            alarms.unwrap_or_default(),
+            stored,
+            led_status.health,
             self.connected_instant.elapsed().as_secs(),
         );
         self.send_ws_response(Response::Status(info), Some(true))
@@ -2,131 +2,1122 @@ use super::serializer::IncomingSerializer as is;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    db::AlarmMode,
+    light::{Animation, BrightnessCurve, EffectName, Escalation, LightDuration, Rgb},
+};
+
 #[derive(Debug)]
 pub enum MessageValues {
     Valid(ParsedMessage),
     Invalid(ErrorData),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "snake_case", tag = "name", content = "body")]
-pub enum ParsedMessage {
-    AddAlarm(AddAlarm),
-    DeleteAll,
-    DeleteOne(DeleteOne),
-    LedStatus,
-    Light { status: bool },
-    Restart,
-    Status,
-    TimeZone(TimeZone),
-}
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case", tag = "name", content = "body")]
+pub enum ParsedMessage {
+    AddAlarm(AddAlarm),
+    AddAnimation(AddAnimation),
+    AddScene(AddScene),
+    AddSunset(AddSunset),
+    Animations,
+    ApplyScene(SceneId),
+    Calibration(Calibration),
+    CalibrationPreview(CalibrationPreview),
+    CalibrationPreviewEnd,
+    DeleteAll,
+    DeleteAnimation(AnimationId),
+    DeleteOne(DeleteOne),
+    DeleteScene(SceneId),
+    DeleteSunset(DeleteSunset),
+    DismissAlarm,
+    Effect(Effect),
+    EffectStop,
+    LedStatus,
+    Light(Light),
+    LightDuration {
+        #[serde(deserialize_with = "is::light_duration")]
+        duration: LightDuration,
+    },
+    NightLight {
+        #[serde(default, deserialize_with = "is::optional_light_duration")]
+        duration: Option<LightDuration>,
+    },
+    NightLightWindow(NightLightWindow),
+    PlayAnimation(PlayAnimation),
+    Restart,
+    Scenes,
+    Snooze {
+        #[serde(default, deserialize_with = "is::snooze_minutes")]
+        minutes: Option<u16>,
+    },
+    Status,
+    Sunset(Sunset),
+    SunriseCurve {
+        curve: BrightnessCurve,
+    },
+    TimeZone(TimeZone),
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct AddAlarm {
+    #[serde(deserialize_with = "is::days")]
+    pub days: Vec<u8>,
+    #[serde(deserialize_with = "is::hour")]
+    pub hour: u8,
+    #[serde(deserialize_with = "is::minute")]
+    pub minute: u8,
+    #[serde(default, deserialize_with = "is::ramp_minutes")]
+    pub ramp_minutes: Option<u16>,
+    #[serde(default, deserialize_with = "is::hold_minutes")]
+    pub hold_minutes: Option<u16>,
+    #[serde(default)]
+    pub mode: AlarmMode,
+    #[serde(default, deserialize_with = "is::optional_id")]
+    pub animation_id: Option<i64>,
+    #[serde(default)]
+    pub escalation: Option<Escalation>,
+    #[serde(default, deserialize_with = "is::escalation_minutes")]
+    pub escalation_minutes: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct AddAnimation {
+    #[serde(deserialize_with = "is::name")]
+    pub name: String,
+    #[serde(deserialize_with = "is::keyframes")]
+    pub keyframes: Animation,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct AnimationId {
+    #[serde(deserialize_with = "is::id")]
+    pub animation_id: i64,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct PlayAnimation {
+    #[serde(deserialize_with = "is::id")]
+    pub animation_id: i64,
+    #[serde(default)]
+    pub looping: bool,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct Calibration {
+    #[serde(deserialize_with = "is::gamma")]
+    pub gamma: (u16, u16, u16),
+    pub white_point: Rgb,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct CalibrationPreview {
+    #[serde(deserialize_with = "is::gamma")]
+    pub gamma: (u16, u16, u16),
+    pub white_point: Rgb,
+    /// Colour shown if the light is off
+    #[serde(default)]
+    pub colour: Option<Rgb>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct AddScene {
+    #[serde(deserialize_with = "is::name")]
+    pub name: String,
+    #[serde(default)]
+    pub colour: Option<Rgb>,
+    #[serde(default, deserialize_with = "is::brightness")]
+    pub brightness: Option<u8>,
+    #[serde(default, deserialize_with = "is::pixels")]
+    pub pixels: Option<Vec<Rgb>>,
+    #[serde(default, deserialize_with = "is::optional_light_duration")]
+    pub duration: Option<LightDuration>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct SceneId {
+    #[serde(deserialize_with = "is::id")]
+    pub scene_id: i64,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct AddSunset {
+    #[serde(deserialize_with = "is::days")]
+    pub days: Vec<u8>,
+    #[serde(deserialize_with = "is::hour")]
+    pub hour: u8,
+    #[serde(deserialize_with = "is::minute")]
+    pub minute: u8,
+    #[serde(default, deserialize_with = "is::brightness")]
+    pub brightness: Option<u8>,
+    #[serde(default)]
+    pub colour: Option<Rgb>,
+    #[serde(default, deserialize_with = "is::sunset_minutes")]
+    pub minutes: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct Sunset {
+    #[serde(default)]
+    pub colour: Option<Rgb>,
+    #[serde(default, deserialize_with = "is::brightness")]
+    pub brightness: Option<u8>,
+    #[serde(default, deserialize_with = "is::sunset_minutes")]
+    pub minutes: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct Effect {
+    pub effect: EffectName,
+    #[serde(default)]
+    pub colour: Option<Rgb>,
+    #[serde(default, deserialize_with = "is::brightness")]
+    pub brightness: Option<u8>,
+    #[serde(default, deserialize_with = "is::period_ms")]
+    pub period_ms: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct Light {
+    pub status: bool,
+    #[serde(default)]
+    pub colour: Option<Rgb>,
+    #[serde(default, deserialize_with = "is::brightness")]
+    pub brightness: Option<u8>,
+    #[serde(default, deserialize_with = "is::pixels")]
+    pub pixels: Option<Vec<Rgb>>,
+    #[serde(default, deserialize_with = "is::optional_light_duration")]
+    pub duration: Option<LightDuration>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct NightLightWindow {
+    #[serde(default, deserialize_with = "is::optional_hour")]
+    pub start: Option<u8>,
+    #[serde(default, deserialize_with = "is::optional_hour")]
+    pub end: Option<u8>,
+    #[serde(deserialize_with = "is::light_duration")]
+    pub duration: LightDuration,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct DeleteOne {
+    #[serde(deserialize_with = "is::id")]
+    pub alarm_id: i64,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct DeleteSunset {
+    #[serde(deserialize_with = "is::id")]
+    pub sunset_id: i64,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct TimeZone {
+    #[serde(deserialize_with = "is::timezone")]
+    pub zone: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+struct StructuredMessage {
+    data: Option<ParsedMessage>,
+    error: Option<ErrorData>,
+    unique: Option<String>,
+}
+
+// TODO - this is, at the moment, pointless
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case", tag = "error", content = "message")]
+pub enum ErrorData {
+    Something(String),
+}
+
+// Change this to a Result<MessageValues, AppError>?
+pub fn to_struct(input: &str) -> Option<MessageValues> {
+    let user_serialized = serde_json::from_str::<StructuredMessage>(input);
+    if let Ok(data) = user_serialized {
+        if let Some(data) = data.error {
+            return Some(MessageValues::Invalid(data));
+        }
+        if let Some(data) = data.data {
+            return Some(MessageValues::Valid(data));
+        }
+        None
+    } else {
+        let error_serialized = serde_json::from_str::<ErrorData>(input);
+        error_serialized.map_or_else(
+            |_| {
+                tracing::debug!("not a known input message");
+                None
+            },
+            |data| {
+                tracing::debug!("Matched error_serialized data");
+                Some(MessageValues::Invalid(data))
+            },
+        )
+    }
+}
+
+/// message_incoming
+///
+/// cargo watch -q -c -w src/ -x 'test message_incoming -- --test-threads=1 --nocapture'
+#[cfg(test)]
+#[expect(clippy::unwrap_used, clippy::too_many_lines)]
+mod tests {
+    use super::*;
+    use crate::blinkt;
+
+    #[test]
+    fn message_incoming_parse_invalid() {
+        let data = "";
+        let result = to_struct(data);
+        assert!(result.is_none());
+
+        let data = "{}";
+        let result = to_struct(data);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_add_alarm_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_alarm",
+                    "body": {
+                        "hour":6,"minute":15,"days":[0,1,2,3,4,5,6]
+                    }
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_some());
+        let result = result.unwrap();
+        match result {
+            MessageValues::Valid(ParsedMessage::AddAlarm(data)) => {
+                assert_eq!(data.days, vec![0, 1, 2, 3, 4, 5, 6]);
+                assert_eq!(data.hour, 6);
+                assert_eq!(data.minute, 15);
+                assert!(data.ramp_minutes.is_none());
+                assert!(data.hold_minutes.is_none());
+                assert_eq!(data.mode, AlarmMode::Start);
+                assert!(data.animation_id.is_none());
+                assert!(data.escalation.is_none());
+                assert!(data.escalation_minutes.is_none());
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_alarm",
+                    "body": {
+                        "hour":6,"minute":15,"days":[0],"ramp_minutes":20,"hold_minutes":60,"mode":"wake","animation_id":2,"escalation":"flash","escalation_minutes":20
+                    }
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_some());
+        match result.unwrap() {
+            MessageValues::Valid(ParsedMessage::AddAlarm(data)) => {
+                assert_eq!(data.ramp_minutes, Some(20));
+                assert_eq!(data.hold_minutes, Some(60));
+                assert_eq!(data.mode, AlarmMode::Wake);
+                assert_eq!(data.animation_id, Some(2));
+                assert_eq!(data.escalation, Some(Escalation::Flash));
+                assert_eq!(data.escalation_minutes, Some(20));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_sunrise_curve_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunrise_curve",
+                    "body": {
+                        "curve":"s_curve"
+                    }
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_some());
+        let result = result.unwrap();
+        match result {
+            MessageValues::Valid(ParsedMessage::SunriseCurve { curve }) => {
+                assert_eq!(curve, BrightnessCurve::SCurve);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_sunrise_curve_invalid() {
+        // Unknown curve
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunrise_curve",
+                    "body": {
+                        "curve":"cubic"
+                    }
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+
+        // No body
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunrise_curve"
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_add_scene_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_scene",
+                    "body": {"name":"reading", "colour":[255,200,120], "brightness":70, "pixels":[[1,2,3]], "duration":"never"}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::AddScene(data)) => {
+                assert_eq!(data.name, "reading");
+                assert_eq!(data.colour, Some((255, 200, 120)));
+                assert_eq!(data.brightness, Some(70));
+                assert_eq!(data.pixels, Some(vec![(1, 2, 3)]));
+                assert_eq!(data.duration, Some(LightDuration::Never));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_scene",
+                    "body": {"name":"relax"}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::AddScene(data)) => {
+                assert_eq!(data.name, "relax");
+                assert!(data.colour.is_none());
+                assert!(data.brightness.is_none());
+                assert!(data.pixels.is_none());
+                assert!(data.duration.is_none());
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_add_scene_invalid() {
+        // No name
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_scene",
+                    "body": {"brightness":70}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // Empty name
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_scene",
+                    "body": {"name":""}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_scene_id() {
+        for name in ["apply_scene", "delete_scene"] {
+            let data = format!(r#"{{"data": {{"name" : "{name}", "body": {{"scene_id":4}}}}}}"#);
+            match to_struct(&data).unwrap() {
+                MessageValues::Valid(
+                    ParsedMessage::ApplyScene(data) | ParsedMessage::DeleteScene(data),
+                ) => {
+                    assert_eq!(data.scene_id, 4);
+                }
+                _ => unreachable!("Shouldn't have matched this"),
+            }
+
+            let data = format!(r#"{{"data": {{"name" : "{name}", "body": {{"scene_id":0}}}}}}"#);
+            assert!(to_struct(&data).is_none());
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_add_animation_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_animation",
+                    "body": {"name":" aurora ", "keyframes":[{"offset_ms":0,"pixels":[[0,0,0]],"brightness":0},{"offset_ms":1500,"pixels":[[0,200,120]],"brightness":60,"easing":"ease_in"}]}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::AddAnimation(data)) => {
+                assert_eq!(data.name, "aurora");
+                assert_eq!(data.keyframes.duration().as_millis(), 1500);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_add_animation_invalid() {
+        // No keyframes
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_animation",
+                    "body": {"name":"aurora", "keyframes":[]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // Offsets out of order
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_animation",
+                    "body": {"name":"aurora", "keyframes":[{"offset_ms":0,"pixels":[],"brightness":0},{"offset_ms":0,"pixels":[],"brightness":10}]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_play_animation() {
+        let data = r#"{"data": {"name" : "play_animation", "body": {"animation_id":3}}}"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::PlayAnimation(data)) => {
+                assert_eq!(data.animation_id, 3);
+                assert!(!data.looping);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data =
+            r#"{"data": {"name" : "play_animation", "body": {"animation_id":3, "looping":true}}}"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::PlayAnimation(data)) => assert!(data.looping),
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"{"data": {"name" : "delete_animation", "body": {"animation_id":3}}}"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::DeleteAnimation(data)) => {
+                assert_eq!(data.animation_id, 3);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"{"data": {"name" : "play_animation", "body": {"animation_id":0}}}"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_calibration_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "calibration",
+                    "body": {"gamma":[220,200,210], "white_point":[255,190,170]}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::Calibration(data)) => {
+                assert_eq!(data.gamma, (220, 200, 210));
+                assert_eq!(data.white_point, (255, 190, 170));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "calibration_preview",
+                    "body": {"gamma":[100,100,100], "white_point":[255,255,255], "colour":[255,200,15]}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::CalibrationPreview(data)) => {
+                assert_eq!(data.gamma, (100, 100, 100));
+                assert_eq!(data.colour, Some((255, 200, 15)));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"{"data": {"name" : "calibration_preview_end"}}"#;
+        assert!(matches!(
+            to_struct(data).unwrap(),
+            MessageValues::Valid(ParsedMessage::CalibrationPreviewEnd)
+        ));
+    }
+
+    #[test]
+    fn message_incoming_parse_calibration_invalid() {
+        // Gamma out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "calibration",
+                    "body": {"gamma":[20,200,210], "white_point":[255,190,170]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // No white point
+        let data = r#"
+            {
+                "data": {
+                    "name" : "calibration_preview",
+                    "body": {"gamma":[100,100,100]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_sunset_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunset",
+                    "body": {}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::Sunset(data)) => {
+                assert!(data.colour.is_none());
+                assert!(data.brightness.is_none());
+                assert!(data.minutes.is_none());
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunset",
+                    "body": {"colour":[255,40,0], "brightness":30, "minutes":45}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::Sunset(data)) => {
+                assert_eq!(data.colour, Some((255, 40, 0)));
+                assert_eq!(data.brightness, Some(30));
+                assert_eq!(data.minutes, Some(45));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_sunset_invalid() {
+        // Minutes out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunset",
+                    "body": {"minutes":181}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // Brightness out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "sunset",
+                    "body": {"brightness":0}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_add_sunset_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_sunset",
+                    "body": {"hour":22,"minute":30,"days":[0,1,2,3,4],"brightness":40,"colour":[255,120,40],"minutes":20}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::AddSunset(data)) => {
+                assert_eq!(data.days, vec![0, 1, 2, 3, 4]);
+                assert_eq!(data.hour, 22);
+                assert_eq!(data.minute, 30);
+                assert_eq!(data.brightness, Some(40));
+                assert_eq!(data.colour, Some((255, 120, 40)));
+                assert_eq!(data.minutes, Some(20));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_add_sunset_invalid() {
+        // No hour
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_sunset",
+                    "body": {"minute":30,"days":[0]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // Hour out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_sunset",
+                    "body": {"hour":24,"minute":30,"days":[0]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_delete_sunset() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "delete_sunset",
+                    "body": {"sunset_id":3}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::DeleteSunset(data)) => {
+                assert_eq!(data.sunset_id, 3);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "delete_sunset",
+                    "body": {"sunset_id":0}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_effect_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "effect",
+                    "body": {"effect":"candle"}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::Effect(data)) => {
+                assert_eq!(data.effect, EffectName::Candle);
+                assert!(data.colour.is_none());
+                assert!(data.brightness.is_none());
+                assert!(data.period_ms.is_none());
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "effect",
+                    "body": {"effect":"breathe", "colour":[0,0,255], "brightness":20, "period_ms":6000}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::Effect(data)) => {
+                assert_eq!(data.effect, EffectName::Breathe);
+                assert_eq!(data.colour, Some((0, 0, 255)));
+                assert_eq!(data.brightness, Some(20));
+                assert_eq!(data.period_ms, Some(6000));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "effect_stop"
+                }
+            }"#;
+        assert!(matches!(
+            to_struct(data),
+            Some(MessageValues::Valid(ParsedMessage::EffectStop))
+        ));
+    }
+
+    #[test]
+    fn message_incoming_parse_animations() {
+        let data = r#"{"data": {"name" : "animations"}}"#;
+        assert!(matches!(
+            to_struct(data),
+            Some(MessageValues::Valid(ParsedMessage::Animations))
+        ));
+    }
+
+    #[test]
+    fn message_incoming_parse_effect_invalid() {
+        // Unknown effect
+        let data = r#"
+            {
+                "data": {
+                    "name" : "effect",
+                    "body": {"effect":"strobe"}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // Period too short
+        let data = r#"
+            {
+                "data": {
+                    "name" : "effect",
+                    "body": {"effect":"pulse", "period_ms":10}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_light_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light",
+                    "body": {"status":true}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_some());
+        match result.unwrap() {
+            MessageValues::Valid(ParsedMessage::Light(data)) => {
+                assert!(data.status);
+                assert!(data.colour.is_none());
+                assert!(data.brightness.is_none());
+                assert!(data.pixels.is_none());
+                assert!(data.duration.is_none());
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light",
+                    "body": {"status":true, "colour":[255,0,10], "brightness":40, "pixels":[[1,2,3],[4,5,6]], "duration":"never"}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_some());
+        match result.unwrap() {
+            MessageValues::Valid(ParsedMessage::Light(data)) => {
+                assert!(data.status);
+                assert_eq!(data.colour, Some((255, 0, 10)));
+                assert_eq!(data.brightness, Some(40));
+                assert_eq!(data.pixels, Some(vec![(1, 2, 3), (4, 5, 6)]));
+                assert_eq!(data.duration, Some(LightDuration::Never));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_light_invalid() {
+        // No status
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light",
+                    "body": {"colour":[255,0,10]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
 
-#[derive(Deserialize, Debug, Serialize)]
-pub struct AddAlarm {
-    #[serde(deserialize_with = "is::days")]
-    pub days: Vec<u8>,
-    #[serde(deserialize_with = "is::hour")]
-    pub hour: u8,
-    #[serde(deserialize_with = "is::minute")]
-    pub minute: u8,
-}
+        // Colour channel out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light",
+                    "body": {"status":true, "colour":[256,0,10]}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
 
-#[derive(Deserialize, Debug, Serialize)]
-pub struct DeleteOne {
-    #[serde(deserialize_with = "is::id")]
-    pub alarm_id: i64,
-}
+        // Brightness out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light",
+                    "body": {"status":true, "brightness":101}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
 
-#[derive(Deserialize, Debug, Serialize)]
-pub struct TimeZone {
-    #[serde(deserialize_with = "is::timezone")]
-    pub zone: String,
-}
+        // Too many pixels
+        let data = serde_json::json!({
+            "data": {
+                "name" : "light",
+                "body": {"status":true, "pixels": vec![[1, 2, 3]; blinkt::MAX_PIXELS + 1]}
+            }
+        })
+        .to_string();
+        assert!(to_struct(&data).is_none());
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "snake_case")]
-struct StructuredMessage {
-    data: Option<ParsedMessage>,
-    error: Option<ErrorData>,
-    unique: Option<String>,
-}
+        // Duration out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light",
+                    "body": {"status":true, "duration":0}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
 
-// TODO - this is, at the moment, pointless
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "snake_case", tag = "error", content = "message")]
-pub enum ErrorData {
-    Something(String),
-}
+    #[test]
+    fn message_incoming_parse_night_light_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "night_light",
+                    "body": {}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::NightLight { duration }) => {
+                assert!(duration.is_none());
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
 
-// Change this to a Result<MessageValues, AppError>?
-pub fn to_struct(input: &str) -> Option<MessageValues> {
-    let user_serialized = serde_json::from_str::<StructuredMessage>(input);
-    if let Ok(data) = user_serialized {
-        if let Some(data) = data.error {
-            return Some(MessageValues::Invalid(data));
+        let data = r#"
+            {
+                "data": {
+                    "name" : "night_light",
+                    "body": {"duration":5}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::NightLight { duration }) => {
+                assert_eq!(duration, Some(LightDuration::Minutes(5)));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
         }
-        if let Some(data) = data.data {
-            return Some(MessageValues::Valid(data));
+    }
+
+    #[test]
+    fn message_incoming_parse_night_light_window_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "night_light_window",
+                    "body": {"start":22, "end":6, "duration":20}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::NightLightWindow(data)) => {
+                assert_eq!(data.start, Some(22));
+                assert_eq!(data.end, Some(6));
+                assert_eq!(data.duration, LightDuration::Minutes(20));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        // Disable the window
+        let data = r#"
+            {
+                "data": {
+                    "name" : "night_light_window",
+                    "body": {"duration":"never"}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::NightLightWindow(data)) => {
+                assert!(data.start.is_none());
+                assert!(data.end.is_none());
+                assert_eq!(data.duration, LightDuration::Never);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
         }
-        None
-    } else {
-        let error_serialized = serde_json::from_str::<ErrorData>(input);
-        error_serialized.map_or_else(
-            |_| {
-                tracing::debug!("not a known input message");
-                None
-            },
-            |data| {
-                tracing::debug!("Matched error_serialized data");
-                Some(MessageValues::Invalid(data))
-            },
-        )
     }
-}
 
-/// message_incoming
-///
-/// cargo watch -q -c -w src/ -x 'test message_incoming -- --test-threads=1 --nocapture'
-#[cfg(test)]
-#[expect(clippy::unwrap_used, clippy::too_many_lines)]
-mod tests {
-    use super::*;
+    #[test]
+    fn message_incoming_parse_night_light_window_invalid() {
+        // Hour out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "night_light_window",
+                    "body": {"start":24, "end":6, "duration":20}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // No duration
+        let data = r#"
+            {
+                "data": {
+                    "name" : "night_light_window",
+                    "body": {"start":22, "end":6}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
 
     #[test]
-    fn message_incoming_parse_invalid() {
-        let data = "";
+    fn message_incoming_parse_light_duration_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light_duration",
+                    "body": {"duration":30}
+                }
+            }"#;
         let result = to_struct(data);
-        assert!(result.is_none());
+        assert!(result.is_some());
+        match result.unwrap() {
+            MessageValues::Valid(ParsedMessage::LightDuration { duration }) => {
+                assert_eq!(duration, LightDuration::Minutes(30));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
 
-        let data = "{}";
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light_duration",
+                    "body": {"duration":"never"}
+                }
+            }"#;
+        match to_struct(data).unwrap() {
+            MessageValues::Valid(ParsedMessage::LightDuration { duration }) => {
+                assert_eq!(duration, LightDuration::Never);
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+    }
+
+    #[test]
+    fn message_incoming_parse_light_duration_invalid() {
+        // Out of range
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light_duration",
+                    "body": {"duration":721}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+
+        // No duration
+        let data = r#"
+            {
+                "data": {
+                    "name" : "light_duration",
+                    "body": {}
+                }
+            }"#;
+        assert!(to_struct(data).is_none());
+    }
+
+    #[test]
+    fn message_incoming_parse_dismiss_alarm_valid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "dismiss_alarm"
+                }
+            }"#;
         let result = to_struct(data);
-        assert!(result.is_none());
+        assert!(result.is_some());
+        assert!(matches!(
+            result.unwrap(),
+            MessageValues::Valid(ParsedMessage::DismissAlarm)
+        ));
     }
 
     #[test]
-    fn message_incoming_parse_add_alarm_valid() {
+    fn message_incoming_parse_snooze_valid() {
         let data = r#"
             {
                 "data": {
-                    "name" : "add_alarm",
-                    "body": {
-                        "hour":6,"minute":15,"days":[0,1,2,3,4,5,6]
-                    }
+                    "name" : "snooze",
+                    "body": {"minutes":5}
                 }
             }"#;
         let result = to_struct(data);
         assert!(result.is_some());
-        let result = result.unwrap();
-        match result {
-            MessageValues::Valid(ParsedMessage::AddAlarm(data)) => {
-                assert_eq!(data.days, vec![0, 1, 2, 3, 4, 5, 6]);
-                assert_eq!(data.hour, 6);
-                assert_eq!(data.minute, 15);
+        match result.unwrap() {
+            MessageValues::Valid(ParsedMessage::Snooze { minutes }) => {
+                assert_eq!(minutes, Some(5));
+            }
+            _ => unreachable!("Shouldn't have matched this"),
+        }
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "snooze",
+                    "body": {}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_some());
+        match result.unwrap() {
+            MessageValues::Valid(ParsedMessage::Snooze { minutes }) => {
+                assert!(minutes.is_none());
             }
             _ => unreachable!("Shouldn't have matched this"),
         }
     }
 
+    #[test]
+    fn message_incoming_parse_snooze_invalid() {
+        let data = r#"
+            {
+                "data": {
+                    "name" : "snooze",
+                    "body": {"minutes":0}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+
+        let data = r#"
+            {
+                "data": {
+                    "name" : "snooze",
+                    "body": {"minutes":"5"}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+    }
+
     #[test]
     fn message_incoming_parse_add_alarm_invalid() {
         // No body
@@ -304,6 +1295,39 @@ mod tests {
         let result = to_struct(data);
         assert!(result.is_none());
 
+        // invalid ramp_minutes - 0
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_alarm",
+                    "body": {"hour":6,"minute":4, "days":[1], "ramp_minutes":0}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+
+        // invalid hold_minutes - > 180
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_alarm",
+                    "body": {"hour":6,"minute":4, "days":[1], "hold_minutes":181}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+
+        // invalid mode
+        let data = r#"
+            {
+                "data": {
+                    "name" : "add_alarm",
+                    "body": {"hour":6,"minute":4, "days":[1], "mode":"sunset"}
+                }
+            }"#;
+        let result = to_struct(data);
+        assert!(result.is_none());
+
         // invalid days - day > 6
         let data = r#"
             {
@@ -1,25 +1,75 @@
 use serde::{Deserialize, Serialize};
 use tokio_tungstenite::tungstenite::Message;
 
-use crate::{db::ModelAlarm, sysinfo::SysInfo};
+use crate::{
+    blinkt::CalibrationSettings,
+    db::{
+        AnimationSummary, ModelAlarm, ModelAlarmOutcome, ModelAnimation, ModelScene, ModelSetting,
+        ModelSunset, NightLightSetting,
+    },
+    light::{BrightnessCurve, LedHealth, LedStatus, LightDuration},
+    sysinfo::SysInfo,
+};
 
 /// Basic pi info
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PiStatus {
     pub alarms: Vec<ModelAlarm>,
+    pub alarm_outcomes: Vec<ModelAlarmOutcome>,
+    /// Stored animations without their keyframes, which are sent in response to an `animations` request
+    pub animations: Vec<AnimationSummary>,
+    pub calibration: CalibrationSettings,
     pub internal_ip: String,
+    pub led_health: LedHealth,
+    pub light_duration: LightDuration,
+    pub night_light: NightLightSetting,
+    pub scenes: Vec<ModelScene>,
+    pub sunrise_curve: BrightnessCurve,
+    pub sunsets: Vec<ModelSunset>,
     pub time_zone: String,
     pub uptime_app: u64,
     pub connected_for: u64,
     pub uptime: usize,
     pub version: String,
 }
+
+/// Everything read from the database that is included in the pi status
+#[derive(Debug, Default)]
+pub struct StoredStatus {
+    pub alarms: Vec<ModelAlarm>,
+    pub alarm_outcomes: Vec<ModelAlarmOutcome>,
+    pub animations: Vec<ModelAnimation>,
+    pub calibration: CalibrationSettings,
+    pub scenes: Vec<ModelScene>,
+    pub setting: ModelSetting,
+    pub sunrise_curve: BrightnessCurve,
+    pub sunsets: Vec<ModelSunset>,
+}
+
 /// Combined pi into and current set alarms
 impl PiStatus {
-    pub fn new(sysinfo: SysInfo, alarms: Vec<ModelAlarm>, connected_for: u64) -> Self {
+    pub fn new(
+        sysinfo: SysInfo,
+        stored: StoredStatus,
+        led_health: LedHealth,
+        connected_for: u64,
+    ) -> Self {
         Self {
-            alarms,
+            alarms: stored.alarms,
+            alarm_outcomes: stored.alarm_outcomes,
+            animations: stored
+                .animations
+                .iter()
+                .map(ModelAnimation::summary)
+                .collect(),
+            calibration: stored.calibration,
             internal_ip: sysinfo.internal_ip,
+            led_health,
+            light_duration: stored.setting.light_duration(),
+            night_light: stored.setting.night_light(),
+            scenes: stored.scenes,
+            sunrise_curve: stored.sunrise_curve,
+            sunsets: stored.sunsets,
             time_zone: sysinfo.time_zone,
             uptime_app: sysinfo.uptime_app,
             uptime: sysinfo.uptime,
@@ -33,7 +83,9 @@ impl PiStatus {
 #[serde(rename_all = "snake_case", tag = "name", content = "data")]
 pub enum Response {
     Status(PiStatus),
-    LedStatus { status: bool },
+    LedStatus(LedStatus),
+    Scenes(Vec<ModelScene>),
+    Animations(Vec<ModelAnimation>),
 }
 
 /// These get sent to the websocket server when in structured_data mode,
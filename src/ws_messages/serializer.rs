@@ -1,5 +1,11 @@
 use serde::{Deserialize, Deserializer, de};
-use std::ops::RangeInclusive;
+use std::{fmt, ops::RangeInclusive};
+
+use crate::{
+    blinkt::MAX_PIXELS,
+    light::{Animation, Keyframe, LightDuration, Rgb},
+};
+
 pub struct IncomingSerializer;
 
 impl IncomingSerializer {
@@ -17,6 +23,35 @@ impl IncomingSerializer {
         Ok(parsed)
     }
 
+    /// Check an optional value is in given range
+    fn optional_in_range<'de, D, T>(
+        deserializer: D,
+        range: RangeInclusive<T>,
+    ) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + PartialOrd + fmt::Display + fmt::Debug,
+    {
+        let parsed = Option::<T>::deserialize(deserializer)?;
+        if let Some(value) = &parsed
+            && !range.contains(value)
+        {
+            return Err(de::Error::custom(format!(
+                "{value}, not in range {range:?}"
+            )));
+        }
+        Ok(parsed)
+    }
+
+    /// Allow only optional u8s from 1 to 100, a brightness percentage
+    pub fn brightness<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 1..=100u8;
+        Self::optional_in_range(deserializer, range)
+    }
+
     /// Allow only vec (json array), max length 7, of items 0 to 6
     pub fn days<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
     where
@@ -36,6 +71,32 @@ impl IncomingSerializer {
         Ok(parsed)
     }
 
+    /// Allow only optional u16s from 1 to 60
+    pub fn escalation_minutes<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 1..=60u16;
+        Self::optional_in_range(deserializer, range)
+    }
+
+    /// Allow only red, green, and blue gammas, in hundredths, from 50 to 300
+    pub fn gamma<'de, D>(deserializer: D) -> Result<(u16, u16, u16), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed = <(u16, u16, u16)>::deserialize(deserializer)?;
+        let range = 50..=300u16;
+        for value in [parsed.0, parsed.1, parsed.2] {
+            if !range.contains(&value) {
+                return Err(de::Error::custom(format!(
+                    "{value}, not in range {range:?}"
+                )));
+            }
+        }
+        Ok(parsed)
+    }
+
     /// Allow only u8s from 0 to 23
     pub fn hour<'de, D>(deserializer: D) -> Result<u8, D::Error>
     where
@@ -45,6 +106,24 @@ impl IncomingSerializer {
         Self::in_range(deserializer, range)
     }
 
+    /// Allow only optional u8s from 0 to 23
+    pub fn optional_hour<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 0..=23u8;
+        Self::optional_in_range(deserializer, range)
+    }
+
+    /// Allow only optional u16s from 0 to 180
+    pub fn hold_minutes<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 0..=180u16;
+        Self::optional_in_range(deserializer, range)
+    }
+
     /// Allow only positive i64, due to sql id issues
     pub fn id<'de, D>(deserializer: D) -> Result<i64, D::Error>
     where
@@ -57,6 +136,89 @@ impl IncomingSerializer {
         Ok(parsed)
     }
 
+    /// Allow only optional positive i64, due to sql id issues
+    pub fn optional_id<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 1..=i64::MAX;
+        Self::optional_in_range(deserializer, range)
+    }
+
+    /// Allow only 1 to 64 keyframes, the first at offset 0, offsets strictly increasing and at most one hour, each with at most MAX_PIXELS pixels and a brightness from 0 to 100, any pixels beyond the length of the strip are ignored
+    pub fn keyframes<'de, D>(deserializer: D) -> Result<Animation, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed = Vec::<Keyframe>::deserialize(deserializer)?;
+        let range = 1..=64;
+        if !range.contains(&parsed.len()) {
+            return Err(de::Error::custom(format!(
+                "keyframes length not in range {range:?}"
+            )));
+        }
+        if parsed[0].offset_ms != 0 {
+            return Err(de::Error::custom("first keyframe offset not 0"));
+        }
+        if parsed
+            .windows(2)
+            .any(|pair| pair[1].offset_ms <= pair[0].offset_ms)
+        {
+            return Err(de::Error::custom("keyframe offsets not increasing"));
+        }
+        if parsed
+            .last()
+            .is_some_and(|keyframe| keyframe.offset_ms > 3_600_000)
+        {
+            return Err(de::Error::custom("animation longer than one hour"));
+        }
+        for keyframe in &parsed {
+            if keyframe.pixels.len() > MAX_PIXELS {
+                return Err(de::Error::custom("too many pixels"));
+            }
+            if keyframe.brightness > 100 {
+                return Err(de::Error::custom(format!(
+                    "{}, not in range 0..=100",
+                    keyframe.brightness
+                )));
+            }
+        }
+        Ok(Animation::new(parsed))
+    }
+
+    /// Check a light duration is either never, or from 1 to 720 minutes
+    fn check_light_duration<E: de::Error>(duration: LightDuration) -> Result<LightDuration, E> {
+        let range = 1..=720u16;
+        if let LightDuration::Minutes(minutes) = duration
+            && !range.contains(&minutes)
+        {
+            return Err(de::Error::custom(format!(
+                "{minutes}, not in range {range:?}"
+            )));
+        }
+        Ok(duration)
+    }
+
+    /// Allow only "never", or u16s from 1 to 720
+    pub fn light_duration<'de, D>(deserializer: D) -> Result<LightDuration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::check_light_duration(LightDuration::deserialize(deserializer)?)
+    }
+
+    /// Allow only optional "never", or u16s from 1 to 720
+    pub fn optional_light_duration<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<LightDuration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<LightDuration>::deserialize(deserializer)?
+            .map(Self::check_light_duration)
+            .transpose()
+    }
+
     /// Allow only u8s from 0 to 59
     pub fn minute<'de, D>(deserializer: D) -> Result<u8, D::Error>
     where
@@ -66,6 +228,75 @@ impl IncomingSerializer {
         Self::in_range(deserializer, range)
     }
 
+    /// Allow only optional u32s from 100 to 60000, the length of an effect cycle in milliseconds
+    pub fn period_ms<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 100..=60_000u32;
+        Self::optional_in_range(deserializer, range)
+    }
+
+    /// Allow only optional vec (json array) of [r, g, b] arrays, min length 1, max length of the longest supported strip, any beyond the length of the configured strip are ignored
+    pub fn pixels<'de, D>(deserializer: D) -> Result<Option<Vec<Rgb>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed = Option::<Vec<Rgb>>::deserialize(deserializer)?;
+        if let Some(pixels) = &parsed {
+            if pixels.is_empty() {
+                return Err(de::Error::custom("no pixels"));
+            }
+            if pixels.len() > MAX_PIXELS {
+                return Err(de::Error::custom("too many pixels"));
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Allow only optional u16s from 1 to 180
+    pub fn ramp_minutes<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 1..=180u16;
+        Self::optional_in_range(deserializer, range)
+    }
+
+    /// Allow only optional u16s from 1 to 60
+    pub fn snooze_minutes<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 1..=60u16;
+        Self::optional_in_range(deserializer, range)
+    }
+
+    /// Allow only strings from 1 to 32 characters, leading and trailing whitespace is removed
+    pub fn name<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed = String::deserialize(deserializer)?;
+        let parsed = parsed.trim();
+        let range = 1..=32;
+        if !range.contains(&parsed.chars().count()) {
+            return Err(de::Error::custom(format!(
+                "name length not in range {range:?}"
+            )));
+        }
+        Ok(parsed.to_owned())
+    }
+
+    /// Allow only optional u16s from 1 to 180
+    pub fn sunset_minutes<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = 1..=180u16;
+        Self::optional_in_range(deserializer, range)
+    }
+
     /// Use timezones crate to make sure is valid timezone
     pub fn timezone<'de, D>(deserializer: D) -> Result<String, D::Error>
     where
@@ -95,6 +326,80 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn incoming_serializer_brightness_err() {
+        let deserializer = serde_json::Value::from(0u8);
+        let result = IncomingSerializer::brightness(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "0, not in range 1..=100");
+
+        let deserializer = serde_json::Value::from(101u8);
+        let result = IncomingSerializer::brightness(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "101, not in range 1..=100");
+    }
+
+    #[test]
+    fn incoming_serializer_brightness_ok() {
+        let deserializer = serde_json::Value::from(50u8);
+        let result = IncomingSerializer::brightness(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(50));
+    }
+
+    #[test]
+    fn incoming_serializer_period_ms_err() {
+        let deserializer = serde_json::Value::from(99u32);
+        let result = IncomingSerializer::period_ms(deserializer);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "99, not in range 100..=60000"
+        );
+    }
+
+    #[test]
+    fn incoming_serializer_period_ms_ok() {
+        let deserializer = serde_json::Value::from(2500u32);
+        let result = IncomingSerializer::period_ms(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(2500));
+    }
+
+    #[test]
+    fn incoming_serializer_pixels_err() {
+        let deserializer = serde_json::json!([]);
+        let result = IncomingSerializer::pixels(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "no pixels");
+
+        let deserializer = serde_json::json!(vec![[1, 2, 3]; MAX_PIXELS + 1]);
+        let result = IncomingSerializer::pixels(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "too many pixels");
+
+        let deserializer = serde_json::json!([[1, 2, 256]]);
+        let result = IncomingSerializer::pixels(deserializer);
+        assert!(result.is_err());
+
+        let deserializer = serde_json::json!([[1, 2]]);
+        let result = IncomingSerializer::pixels(deserializer);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn incoming_serializer_pixels_ok() {
+        let deserializer = serde_json::json!([[255, 0, 0], [0, 255, 0]]);
+        let result = IncomingSerializer::pixels(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(vec![(255, 0, 0), (0, 255, 0)]));
+
+        let deserializer = serde_json::Value::Null;
+        let result = IncomingSerializer::pixels(deserializer);
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_none());
+    }
+
     #[test]
     fn incoming_serializer_days_err() {
         let deserializer: SeqDeserializer<std::vec::IntoIter<u8>, ValueError> =
@@ -119,6 +424,55 @@ mod tests {
         assert_eq!(result.unwrap(), [0, 1, 2, 3, 4, 5, 6,]);
     }
 
+    #[test]
+    fn incoming_serializer_escalation_minutes_err() {
+        for value in [0u16, 61] {
+            let deserializer = serde_json::Value::from(value);
+            let result = IncomingSerializer::escalation_minutes(deserializer);
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("{value}, not in range 1..=60")
+            );
+        }
+    }
+
+    #[test]
+    fn incoming_serializer_escalation_minutes_ok() {
+        let deserializer = serde_json::Value::from(60u16);
+        let result = IncomingSerializer::escalation_minutes(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(60));
+    }
+
+    #[test]
+    fn incoming_serializer_gamma_err() {
+        let deserializer = serde_json::json!([100, 49, 100]);
+        let result = IncomingSerializer::gamma(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "49, not in range 50..=300");
+
+        let deserializer = serde_json::json!([100, 100, 301]);
+        let result = IncomingSerializer::gamma(deserializer);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "301, not in range 50..=300"
+        );
+
+        let deserializer = serde_json::json!([100, 100]);
+        let result = IncomingSerializer::gamma(deserializer);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn incoming_serializer_gamma_ok() {
+        let deserializer = serde_json::json!([50, 220, 300]);
+        let result = IncomingSerializer::gamma(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), (50, 220, 300));
+    }
+
     #[test]
     fn incoming_serializer_id_err() {
         let deserializer: I64Deserializer<ValueError> = 0i64.into_deserializer();
@@ -135,6 +489,125 @@ mod tests {
         assert_eq!(result.unwrap(), 10i64);
     }
 
+    #[test]
+    fn incoming_serializer_optional_id_err() {
+        let deserializer = serde_json::Value::from(0i64);
+        let result = IncomingSerializer::optional_id(deserializer);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "0, not in range 1..=9223372036854775807"
+        );
+    }
+
+    #[test]
+    fn incoming_serializer_optional_id_ok() {
+        let deserializer = serde_json::Value::from(3i64);
+        let result = IncomingSerializer::optional_id(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(3));
+
+        let deserializer = serde_json::Value::Null;
+        let result = IncomingSerializer::optional_id(deserializer);
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn incoming_serializer_keyframes_err() {
+        let keyframe = |offset_ms: u32, brightness: u8| serde_json::json!({"offset_ms": offset_ms, "pixels": [[255, 0, 0]], "brightness": brightness});
+        let test = |deserializer: serde_json::Value, message: &str| {
+            let result = IncomingSerializer::keyframes(deserializer);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err().to_string(), message);
+        };
+
+        test(
+            serde_json::json!([]),
+            "keyframes length not in range 1..=64",
+        );
+        test(
+            serde_json::json!(vec![keyframe(0, 10); 65]),
+            "keyframes length not in range 1..=64",
+        );
+        test(
+            serde_json::json!([keyframe(10, 10)]),
+            "first keyframe offset not 0",
+        );
+        test(
+            serde_json::json!([keyframe(0, 10), keyframe(500, 10), keyframe(500, 10)]),
+            "keyframe offsets not increasing",
+        );
+        test(
+            serde_json::json!([keyframe(0, 10), keyframe(3_600_001, 10)]),
+            "animation longer than one hour",
+        );
+        test(
+            serde_json::json!([keyframe(0, 101)]),
+            "101, not in range 0..=100",
+        );
+        test(
+            serde_json::json!([{"offset_ms": 0, "pixels": vec![[1, 2, 3]; MAX_PIXELS + 1], "brightness": 10}]),
+            "too many pixels",
+        );
+
+        let deserializer = serde_json::json!([{"offset_ms": 0, "pixels": [], "brightness": 10, "easing": "bounce"}]);
+        assert!(IncomingSerializer::keyframes(deserializer).is_err());
+    }
+
+    #[test]
+    fn incoming_serializer_keyframes_ok() {
+        let deserializer = serde_json::json!([
+            {"offset_ms": 0, "pixels": [[255, 0, 0]], "brightness": 0},
+            {"offset_ms": 3_600_000, "pixels": [], "brightness": 100, "easing": "step"}
+        ]);
+        let result = IncomingSerializer::keyframes(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().duration(),
+            std::time::Duration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn incoming_serializer_light_duration_err() {
+        let deserializer = serde_json::Value::from(0u16);
+        let result = IncomingSerializer::light_duration(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "0, not in range 1..=720");
+
+        let deserializer = serde_json::Value::from(721u16);
+        let result = IncomingSerializer::optional_light_duration(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "721, not in range 1..=720");
+
+        let deserializer = serde_json::Value::from("always");
+        let result = IncomingSerializer::light_duration(deserializer);
+        assert!(result.is_err());
+
+        let deserializer = serde_json::Value::Null;
+        let result = IncomingSerializer::light_duration(deserializer);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn incoming_serializer_light_duration_ok() {
+        let deserializer = serde_json::Value::from(720u16);
+        let result = IncomingSerializer::light_duration(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), LightDuration::Minutes(720));
+
+        let deserializer = serde_json::Value::from("never");
+        let result = IncomingSerializer::optional_light_duration(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(LightDuration::Never));
+
+        let deserializer = serde_json::Value::Null;
+        let result = IncomingSerializer::optional_light_duration(deserializer);
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_none());
+    }
+
     #[test]
     fn incoming_serializer_minute_err() {
         let deserializer: U8Deserializer<ValueError> = 60u8.into_deserializer();
@@ -167,6 +640,124 @@ mod tests {
         assert_eq!(result.unwrap(), 23u8);
     }
 
+    #[test]
+    fn incoming_serializer_optional_hour_err() {
+        let deserializer = serde_json::Value::from(24u8);
+        let result = IncomingSerializer::optional_hour(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "24, not in range 0..=23");
+    }
+
+    #[test]
+    fn incoming_serializer_optional_hour_ok() {
+        let deserializer = serde_json::Value::from(0u8);
+        let result = IncomingSerializer::optional_hour(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(0));
+
+        let deserializer = serde_json::Value::Null;
+        let result = IncomingSerializer::optional_hour(deserializer);
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn incoming_serializer_ramp_minutes_err() {
+        let deserializer = serde_json::Value::from(0u16);
+        let result = IncomingSerializer::ramp_minutes(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "0, not in range 1..=180");
+
+        let deserializer = serde_json::Value::from(181u16);
+        let result = IncomingSerializer::ramp_minutes(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "181, not in range 1..=180");
+    }
+
+    #[test]
+    fn incoming_serializer_ramp_minutes_ok() {
+        let deserializer = serde_json::Value::from(20u16);
+        let result = IncomingSerializer::ramp_minutes(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(20));
+
+        let deserializer = serde_json::Value::Null;
+        let result = IncomingSerializer::ramp_minutes(deserializer);
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn incoming_serializer_hold_minutes_err() {
+        let deserializer = serde_json::Value::from(181u16);
+        let result = IncomingSerializer::hold_minutes(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "181, not in range 0..=180");
+    }
+
+    #[test]
+    fn incoming_serializer_hold_minutes_ok() {
+        let deserializer = serde_json::Value::from(0u16);
+        let result = IncomingSerializer::hold_minutes(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(0));
+    }
+
+    #[test]
+    fn incoming_serializer_snooze_minutes_err() {
+        let deserializer = serde_json::Value::from(61u16);
+        let result = IncomingSerializer::snooze_minutes(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "61, not in range 1..=60");
+    }
+
+    #[test]
+    fn incoming_serializer_snooze_minutes_ok() {
+        let deserializer = serde_json::Value::from(9u16);
+        let result = IncomingSerializer::snooze_minutes(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(9));
+    }
+
+    #[test]
+    fn incoming_serializer_name_err() {
+        let deserializer: StringDeserializer<ValueError> = S!("   ").into_deserializer();
+        let result = IncomingSerializer::name(deserializer);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "name length not in range 1..=32"
+        );
+
+        let deserializer: StringDeserializer<ValueError> = "a".repeat(33).into_deserializer();
+        let result = IncomingSerializer::name(deserializer);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn incoming_serializer_name_ok() {
+        let deserializer: StringDeserializer<ValueError> = S!(" reading ").into_deserializer();
+        let result = IncomingSerializer::name(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "reading");
+    }
+
+    #[test]
+    fn incoming_serializer_sunset_minutes_err() {
+        let deserializer = serde_json::Value::from(0u16);
+        let result = IncomingSerializer::sunset_minutes(deserializer);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "0, not in range 1..=180");
+    }
+
+    #[test]
+    fn incoming_serializer_sunset_minutes_ok() {
+        let deserializer = serde_json::Value::from(45u16);
+        let result = IncomingSerializer::sunset_minutes(deserializer);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(45));
+    }
+
     #[test]
     fn incoming_serializer_timezone_err() {
         let deserializer: StringDeserializer<ValueError> =